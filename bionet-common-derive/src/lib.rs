@@ -0,0 +1,174 @@
+use proc_macro::TokenStream;
+
+use proc_macro2::{ Span, TokenStream as TokenStream2 };
+
+use quote::{ quote, format_ident };
+
+use syn::{
+    parse_macro_input, DeriveInput, Data, Fields, Index, Path, LitStr, Error, Result
+};
+
+enum Category {
+    Numerical,
+    Categorical,
+    Ordinal
+}
+
+enum DistanceKind {
+    Categorical,
+    Fields,
+    With(Path)
+}
+
+struct SensorAttributes {
+    category: Category,
+    distance: DistanceKind
+}
+
+// #[sensor(category = "numerical" | "categorical" | "ordinal")]
+// #[sensor(distance = "categorical" | "fields")]
+// #[sensor(distance_with = "path::to::fn")], fn(&Self, &Self) -> f64
+fn sensor_attributes(input: &DeriveInput) -> Result<SensorAttributes> {
+    let mut attributes = SensorAttributes {
+        category: Category::Categorical, distance: DistanceKind::Categorical
+    };
+
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("sensor")) {
+        attr.parse_nested_meta(|meta| {
+            let value: LitStr = meta.value()?.parse()?;
+            if meta.path.is_ident("category") {
+                attributes.category = match value.value().as_str() {
+                    "numerical" => Category::Numerical,
+                    "categorical" => Category::Categorical,
+                    "ordinal" => Category::Ordinal,
+                    _ => return Err(Error::new(
+                        value.span(), "expected \"numerical\", \"categorical\" or \"ordinal\""
+                    ))
+                };
+            } else if meta.path.is_ident("distance") {
+                attributes.distance = match value.value().as_str() {
+                    "categorical" => DistanceKind::Categorical,
+                    "fields" => DistanceKind::Fields,
+                    _ => return Err(Error::new(
+                        value.span(), "expected \"categorical\" or \"fields\""
+                    ))
+                };
+            } else if meta.path.is_ident("distance_with") {
+                attributes.distance = DistanceKind::With(value.parse()?);
+            } else {
+                return Err(meta.error("unknown sensor attribute"))
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(attributes)
+}
+
+fn fields_distance(input: &DeriveInput) -> Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(Error::new(
+            Span::call_site(), "field-wise distance is supported only for structs"
+        ))
+    };
+
+    let members: Vec<TokenStream2> = match fields {
+        Fields::Named(fields) => fields.named.iter().map(|field| {
+            let ident = field.ident.as_ref().unwrap();
+            quote! { #ident }
+        }).collect(),
+        Fields::Unnamed(fields) => (0..fields.unnamed.len()).map(|i| {
+            let index = Index::from(i);
+            quote! { #index }
+        }).collect(),
+        Fields::Unit => Vec::new()
+    };
+
+    Ok(quote! {
+        0.0 #( + ::bionet_common::sensor::SensorData::distance(&self.#members, &rhs.#members) )*
+    })
+}
+
+fn expand_sensor_data(input: &DeriveInput) -> Result<TokenStream2> {
+    let attributes = sensor_attributes(input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let distance = match &attributes.distance {
+        DistanceKind::Categorical => quote! { if self == rhs { 0.0 } else { 1.0 } },
+        DistanceKind::Fields => fields_distance(input)?,
+        DistanceKind::With(path) => quote! { #path(self, rhs) }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::bionet_common::sensor::SensorData for #name #ty_generics
+        #where_clause {
+            fn equals(&self, rhs: &dyn ::bionet_common::sensor::SensorData) -> bool {
+                ::bionet_common::sensor::AnyCast::any(rhs)
+                    .downcast_ref::<Self>()
+                    .map(|rhs| rhs == self)
+                    .unwrap_or(false)
+            }
+
+            fn partial_compare(
+                &self, rhs: &dyn ::bionet_common::sensor::SensorData
+            ) -> Option<::std::cmp::Ordering> {
+                let rhs = ::bionet_common::sensor::AnyCast::any(rhs).downcast_ref::<Self>()?;
+                ::std::cmp::PartialOrd::partial_cmp(self, rhs)
+            }
+
+            fn distance(&self, rhs: &dyn ::bionet_common::sensor::SensorData) -> f64 {
+                let rhs = ::bionet_common::sensor::AnyCast::any(rhs)
+                    .downcast_ref::<Self>()
+                    .unwrap();
+                #distance
+            }
+        }
+    })
+}
+
+fn expand_data_deductor(input: &DeriveInput) -> Result<TokenStream2> {
+    let attributes = sensor_attributes(input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let category = format_ident!("{}", match attributes.category {
+        Category::Numerical => "Numerical",
+        Category::Categorical => "Categorical",
+        Category::Ordinal => "Ordinal"
+    });
+
+    Ok(quote! {
+        ::bionet_common::impl_known_data_type! {
+            [#impl_generics] #name #ty_generics, [#where_clause]
+        }
+
+        impl #impl_generics ::bionet_common::data::DataDeductor for #name #ty_generics
+        #where_clause {
+            fn data_type(&self) -> ::bionet_common::data::DataType {
+                ::bionet_common::data::DataType::Unknown
+            }
+
+            fn data_category(&self) -> ::bionet_common::data::DataCategory {
+                ::bionet_common::data::DataCategory::#category
+            }
+        }
+    })
+}
+
+/// Implements `SensorData` using `PartialEq`, `PartialOrd` and the distance chosen with
+/// `#[sensor(distance = "categorical" | "fields")]` or `#[sensor(distance_with = "path")]`.
+#[proc_macro_derive(SensorData, attributes(sensor))]
+pub fn derive_sensor_data(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_sensor_data(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Implements `DataDeductor` with the category chosen with `#[sensor(category = "...")]`,
+/// no nightly features are needed in the deriving crate.
+#[proc_macro_derive(DataDeductor, attributes(sensor))]
+pub fn derive_data_deductor(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_data_deductor(&input).unwrap_or_else(Error::into_compile_error).into()
+}
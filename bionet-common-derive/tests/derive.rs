@@ -0,0 +1,72 @@
+use std::fmt::{ Display, Formatter, Result as FmtResult };
+
+use bionet_common::{
+    sensor::SensorData,
+    data::{ DataCategory, DataDeductor, DataType }
+};
+
+#[derive(Debug, Clone, PartialEq, PartialOrd, SensorData, DataDeductor)]
+#[sensor(category = "numerical", distance = "fields")]
+struct Color {
+    r: u8,
+    g: u8,
+    b: u8
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd, SensorData, DataDeductor)]
+struct Sku(String);
+
+#[derive(Debug, Clone, PartialEq, PartialOrd, SensorData, DataDeductor)]
+#[sensor(category = "ordinal", distance_with = "size_distance")]
+enum Size {
+    Small,
+    Medium,
+    Large
+}
+
+fn size_distance(lhs: &Size, rhs: &Size) -> f64 {
+    (lhs.clone() as i32 - rhs.clone() as i32).abs() as f64 / 2.0
+}
+
+macro_rules! impl_display {
+    ( $($t:ty),* ) => {
+        $( impl Display for $t {
+            fn fmt(&self, f: &mut Formatter) -> FmtResult { write!(f, "{:?}", self) }
+        }) *
+    }
+}
+
+impl_display! { Color, Sku, Size }
+
+#[derive(Debug, Clone, PartialEq, PartialOrd, DataDeductor)]
+#[sensor(category = "numerical")]
+struct Reading<T: Copy> where T: Into<f64> {
+    value: T
+}
+
+#[test]
+fn derived_sensor_data() {
+    let red = Color { r: 255, g: 0, b: 0 };
+    let pink = Color { r: 255, g: 100, b: 150 };
+    assert!(red.equals(&red.clone()));
+    assert!(!red.equals(&Sku("red".to_string())));
+    assert_eq!(SensorData::distance(&red, &pink), 250.0);
+    assert_eq!(red.partial_compare(&pink), Some(std::cmp::Ordering::Less));
+    assert_eq!(red.partial_compare(&Size::Small), None);
+
+    let sku = Sku("A-1".to_string());
+    assert_eq!(SensorData::distance(&sku, &Sku("A-1".to_string())), 0.0);
+    assert_eq!(SensorData::distance(&sku, &Sku("B-2".to_string())), 1.0);
+
+    assert_eq!(SensorData::distance(&Size::Small, &Size::Large), 1.0);
+}
+
+#[test]
+fn derived_data_deductor() {
+    let red = Color { r: 255, g: 0, b: 0 };
+    assert_eq!(red.data_type(), DataType::Unknown);
+    assert_eq!(red.data_category(), DataCategory::Numerical);
+    assert_eq!(Sku("A-1".to_string()).data_category(), DataCategory::Categorical);
+    assert_eq!(Size::Medium.data_category(), DataCategory::Ordinal);
+    assert_eq!(Reading { value: 2.5f32 }.data_category(), DataCategory::Numerical);
+}
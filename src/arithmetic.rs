@@ -0,0 +1,399 @@
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    mem,
+    ops::{ Add, Sub, Mul, Div },
+    fmt::{ Display, Formatter, Result as FmtResult }
+};
+
+use crate::{
+    data::{ DataType, DataTypeValue, DataDeductor },
+    numeric::{ self, Numeric }
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArithmeticError {
+    NotNumeric(String),
+    Incomparable(String, String),
+    Overflow,
+    DivisionByZero,
+    NotEnoughValues { required: usize, found: usize }
+}
+
+impl Display for ArithmeticError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            ArithmeticError::NotNumeric(v) => write!(f, "{} is not numeric", v),
+            ArithmeticError::Incomparable(lhs, rhs) => {
+                write!(f, "{} and {} cannot be compared", lhs, rhs)
+            }
+            ArithmeticError::Overflow => write!(f, "arithmetic overflow"),
+            ArithmeticError::DivisionByZero => write!(f, "division by zero"),
+            ArithmeticError::NotEnoughValues { required, found } => {
+                write!(f, "at least {} values required, found {}", required, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArithmeticError {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Operation { Add, Sub, Mul, Div }
+
+fn numeric(value: &DataTypeValue) -> Result<Numeric, ArithmeticError> {
+    value.to_numeric().ok_or_else(|| ArithmeticError::NotNumeric(value.to_string()))
+}
+
+fn checked_float(lhs: f64, rhs: f64, operation: Operation) -> Result<f64, ArithmeticError> {
+    if operation == Operation::Div && rhs == 0.0 { return Err(ArithmeticError::DivisionByZero) }
+    let result = match operation {
+        Operation::Add => lhs + rhs,
+        Operation::Sub => lhs - rhs,
+        Operation::Mul => lhs * rhs,
+        Operation::Div => lhs / rhs
+    };
+    if result.is_infinite() && lhs.is_finite() && rhs.is_finite() {
+        Err(ArithmeticError::Overflow)
+    } else {
+        Ok(result)
+    }
+}
+
+// sign and magnitude of an integer
+fn sign_magnitude(number: Numeric) -> Option<(bool, u128)> {
+    match number {
+        Numeric::Signed(v) => Some((v < 0, v.unsigned_abs())),
+        Numeric::Unsigned(v) => Some((false, v)),
+        Numeric::Float(_) => None
+    }
+}
+
+// u128 mixed with a signed integer, which promote turns into f64, computed exactly,
+// the result is I128 like the sum of such values
+fn checked_wide(
+    (lhs_negative, lhs): (bool, u128), (rhs_negative, rhs): (bool, u128), operation: Operation
+) -> Result<DataTypeValue, ArithmeticError> {
+    let (negative, magnitude) = match operation {
+        Operation::Add | Operation::Sub => {
+            let rhs_negative = rhs_negative != (operation == Operation::Sub);
+            if lhs_negative == rhs_negative {
+                (lhs_negative, lhs.checked_add(rhs).ok_or(ArithmeticError::Overflow)?)
+            } else if lhs >= rhs {
+                (lhs_negative, lhs - rhs)
+            } else {
+                (rhs_negative, rhs - lhs)
+            }
+        }
+        Operation::Mul => {
+            (lhs_negative != rhs_negative, lhs.checked_mul(rhs).ok_or(ArithmeticError::Overflow)?)
+        }
+        Operation::Div => {
+            if rhs == 0 { return Err(ArithmeticError::DivisionByZero) }
+            (lhs_negative != rhs_negative, lhs / rhs)
+        }
+    };
+    let result = if negative {
+        0i128.checked_sub_unsigned(magnitude)
+    } else {
+        i128::try_from(magnitude).ok()
+    };
+    result.map(DataTypeValue::I128).ok_or(ArithmeticError::Overflow)
+}
+
+fn checked_operation(
+    lhs: &DataTypeValue, rhs: &DataTypeValue, operation: Operation
+) -> Result<DataTypeValue, ArithmeticError> {
+    let (lhs_number, rhs_number) = (numeric(lhs)?, numeric(rhs)?);
+    let data_type = numeric::promote(&lhs.data_type(), &rhs.data_type())
+        .ok_or_else(|| ArithmeticError::NotNumeric(lhs.to_string()))?;
+    if let (DataType::F64, Some(lhs), Some(rhs)) =
+        (data_type, sign_magnitude(lhs_number), sign_magnitude(rhs_number)) {
+        return checked_wide(lhs, rhs, operation)
+    }
+    let lhs = lhs_number.to_value(&data_type).ok_or(ArithmeticError::Overflow)?;
+    let rhs = rhs_number.to_value(&data_type).ok_or(ArithmeticError::Overflow)?;
+
+    macro_rules! integer {
+        ($lhs:expr, $rhs:expr, $variant:ident) => {{
+            if operation == Operation::Div && *$rhs == 0 {
+                return Err(ArithmeticError::DivisionByZero)
+            }
+            let result = match operation {
+                Operation::Add => $lhs.checked_add(*$rhs),
+                Operation::Sub => $lhs.checked_sub(*$rhs),
+                Operation::Mul => $lhs.checked_mul(*$rhs),
+                Operation::Div => $lhs.checked_div(*$rhs)
+            };
+            result.map(DataTypeValue::$variant).ok_or(ArithmeticError::Overflow)
+        }}
+    }
+
+    match (&lhs, &rhs) {
+        (DataTypeValue::U8(l), DataTypeValue::U8(r)) => integer!(l, r, U8),
+        (DataTypeValue::U16(l), DataTypeValue::U16(r)) => integer!(l, r, U16),
+        (DataTypeValue::U32(l), DataTypeValue::U32(r)) => integer!(l, r, U32),
+        (DataTypeValue::U64(l), DataTypeValue::U64(r)) => integer!(l, r, U64),
+        (DataTypeValue::U128(l), DataTypeValue::U128(r)) => integer!(l, r, U128),
+        (DataTypeValue::USize(l), DataTypeValue::USize(r)) => integer!(l, r, USize),
+        (DataTypeValue::I8(l), DataTypeValue::I8(r)) => integer!(l, r, I8),
+        (DataTypeValue::I16(l), DataTypeValue::I16(r)) => integer!(l, r, I16),
+        (DataTypeValue::I32(l), DataTypeValue::I32(r)) => integer!(l, r, I32),
+        (DataTypeValue::I64(l), DataTypeValue::I64(r)) => integer!(l, r, I64),
+        (DataTypeValue::I128(l), DataTypeValue::I128(r)) => integer!(l, r, I128),
+        (DataTypeValue::ISize(l), DataTypeValue::ISize(r)) => integer!(l, r, ISize),
+        (DataTypeValue::F32(l), DataTypeValue::F32(r)) => {
+            let result = checked_float(*l as f64, *r as f64, operation)? as f32;
+            if result.is_infinite() && l.is_finite() && r.is_finite() {
+                return Err(ArithmeticError::Overflow)
+            }
+            Ok(DataTypeValue::F32(result))
+        }
+        (DataTypeValue::F64(l), DataTypeValue::F64(r)) => {
+            Ok(DataTypeValue::F64(checked_float(*l, *r, operation)?))
+        }
+        _ => Err(ArithmeticError::NotNumeric(lhs.to_string()))
+    }
+}
+
+macro_rules! impl_operation {
+    ( $($trait:ident, $method:ident, $operation:expr);* ) => {
+        $( impl $trait for DataTypeValue {
+            type Output = Result<DataTypeValue, ArithmeticError>;
+
+            fn $method(self, rhs: DataTypeValue) -> Self::Output {
+                checked_operation(&self, &rhs, $operation)
+            }
+        }
+
+        impl $trait for &DataTypeValue {
+            type Output = Result<DataTypeValue, ArithmeticError>;
+
+            fn $method(self, rhs: &DataTypeValue) -> Self::Output {
+                checked_operation(self, rhs, $operation)
+            }
+        }) *
+    }
+}
+
+impl_operation! {
+    Add, add, Operation::Add;
+    Sub, sub, Operation::Sub;
+    Mul, mul, Operation::Mul;
+    Div, div, Operation::Div
+}
+
+// integer sums are accumulated exactly and returned as 64-bit integers, or 128-bit ones
+// when any input already is, float sums are returned as f64 unless every input is f32
+pub fn sum<T, I>(values: I) -> Result<DataTypeValue, ArithmeticError>
+where T: Borrow<DataTypeValue>, I: IntoIterator<Item = T> {
+    let mut signed: i128 = 0;
+    let mut unsigned: u128 = 0;
+    let mut float: f64 = 0.0;
+    let (mut has_signed, mut has_float, mut all_f32, mut wide) = (false, false, true, false);
+    let mut all_finite = true;
+
+    for value in values {
+        let value = value.borrow();
+        match numeric(value)? {
+            Numeric::Signed(v) => {
+                signed = signed.checked_add(v).ok_or(ArithmeticError::Overflow)?;
+                has_signed = true;
+                all_f32 = false;
+            }
+            Numeric::Unsigned(v) => {
+                unsigned = unsigned.checked_add(v).ok_or(ArithmeticError::Overflow)?;
+                all_f32 = false;
+            }
+            Numeric::Float(v) => {
+                float += v;
+                has_float = true;
+                all_finite &= v.is_finite();
+                all_f32 &= matches!(value, DataTypeValue::F32(_));
+            }
+        }
+        wide |= matches!(value, DataTypeValue::I128(_) | DataTypeValue::U128(_));
+    }
+
+    if has_float {
+        // as in checked_float, only finite inputs can overflow
+        let total = float + signed as f64 + unsigned as f64;
+        let total = if all_f32 { total as f32 as f64 } else { total };
+        if total.is_infinite() && all_finite { return Err(ArithmeticError::Overflow) }
+        return Ok(if all_f32 { DataTypeValue::F32(total as f32) } else { DataTypeValue::F64(total) })
+    }
+
+    let total = if has_signed {
+        let unsigned = i128::try_from(unsigned).map_err(|_| ArithmeticError::Overflow)?;
+        Numeric::Signed(signed.checked_add(unsigned).ok_or(ArithmeticError::Overflow)?)
+    } else {
+        Numeric::Unsigned(unsigned)
+    };
+    let data_type = match (has_signed, wide) {
+        (true, true) => DataType::I128,
+        (true, false) => DataType::I64,
+        (false, true) => DataType::U128,
+        (false, false) => DataType::U64
+    };
+    total.to_value(&data_type).ok_or(ArithmeticError::Overflow)
+}
+
+fn floats<T, I>(values: I) -> Result<Vec<f64>, ArithmeticError>
+where T: Borrow<DataTypeValue>, I: IntoIterator<Item = T> {
+    values.into_iter().map(|value| Ok(numeric(value.borrow())?.to_f64())).collect()
+}
+
+pub fn mean<T, I>(values: I) -> Result<DataTypeValue, ArithmeticError>
+where T: Borrow<DataTypeValue>, I: IntoIterator<Item = T> {
+    let values = floats(values)?;
+    if values.is_empty() {
+        return Err(ArithmeticError::NotEnoughValues { required: 1, found: 0 })
+    }
+    Ok(DataTypeValue::F64(values.iter().sum::<f64>() / values.len() as f64))
+}
+
+// sample variance with n - 1 degrees of freedom
+pub fn variance<T, I>(values: I) -> Result<DataTypeValue, ArithmeticError>
+where T: Borrow<DataTypeValue>, I: IntoIterator<Item = T> {
+    let values = floats(values)?;
+    if values.len() < 2 {
+        return Err(ArithmeticError::NotEnoughValues { required: 2, found: values.len() })
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let squares: f64 = values.iter().map(|v| (v - mean) * (v - mean)).sum();
+    Ok(DataTypeValue::F64(squares / (values.len() - 1) as f64))
+}
+
+fn comparable(lhs: &DataTypeValue, rhs: &DataTypeValue) -> bool {
+    match (lhs.to_numeric(), rhs.to_numeric()) {
+        (Some(_), Some(_)) => true,
+        (None, None) => mem::discriminant(lhs) == mem::discriminant(rhs),
+        _ => false
+    }
+}
+
+fn extreme<T, I>(values: I, wanted: Ordering) -> Result<DataTypeValue, ArithmeticError>
+where T: Borrow<DataTypeValue>, I: IntoIterator<Item = T> {
+    let mut result: Option<DataTypeValue> = None;
+    for value in values {
+        let value = value.borrow();
+        if value.to_numeric().map(|v| v.is_nan()).unwrap_or(false) { continue }
+        result = match result {
+            None => Some(value.clone()),
+            Some(current) => {
+                let ordering = if comparable(value, &current) {
+                    value.partial_cmp(&current)
+                } else {
+                    None
+                };
+                match ordering {
+                    Some(ordering) if ordering == wanted => Some(value.clone()),
+                    Some(_) => Some(current),
+                    None => return Err(
+                        ArithmeticError::Incomparable(value.to_string(), current.to_string())
+                    )
+                }
+            }
+        };
+    }
+    result.ok_or(ArithmeticError::NotEnoughValues { required: 1, found: 0 })
+}
+
+// NaNs are skipped, any other values comparable with each other are accepted
+pub fn min<T, I>(values: I) -> Result<DataTypeValue, ArithmeticError>
+where T: Borrow<DataTypeValue>, I: IntoIterator<Item = T> {
+    extreme(values, Ordering::Less)
+}
+
+pub fn max<T, I>(values: I) -> Result<DataTypeValue, ArithmeticError>
+where T: Borrow<DataTypeValue>, I: IntoIterator<Item = T> {
+    extreme(values, Ordering::Greater)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{ ArithmeticError, sum, mean, variance, min, max };
+    use crate::data::DataTypeValue;
+
+    #[test]
+    fn checked_operations() {
+        assert_eq!(DataTypeValue::U8(200) + DataTypeValue::I8(-1), Ok(DataTypeValue::I16(199)));
+        assert!(matches!(
+            DataTypeValue::U8(200) + DataTypeValue::U8(100), Err(ArithmeticError::Overflow)
+        ));
+        assert!(matches!(
+            &DataTypeValue::I32(7) / &DataTypeValue::U8(0), Err(ArithmeticError::DivisionByZero)
+        ));
+        assert!(matches!(
+            DataTypeValue::I32(i32::MIN) / DataTypeValue::I32(-1), Err(ArithmeticError::Overflow)
+        ));
+        assert!(matches!(
+            DataTypeValue::F64(1.5) * DataTypeValue::I64(2), Ok(DataTypeValue::F64(v)) if v == 3.0
+        ));
+        assert!(matches!(
+            DataTypeValue::Bool(true) - DataTypeValue::U8(1), Err(ArithmeticError::NotNumeric(_))
+        ));
+
+        let wide = DataTypeValue::U128((1 << 100) + 1);
+        assert_eq!(&wide - &DataTypeValue::I8(1), Ok(DataTypeValue::I128(1 << 100)));
+        assert_eq!(
+            DataTypeValue::I64(-3) * DataTypeValue::U128(1 << 100),
+            Ok(DataTypeValue::I128(-3 << 100))
+        );
+        assert_eq!(
+            DataTypeValue::I8(-7) / DataTypeValue::U128(2), Ok(DataTypeValue::I128(-3))
+        );
+        assert_eq!(
+            DataTypeValue::U128(1 << 127) + DataTypeValue::I128(i128::MIN),
+            Ok(DataTypeValue::I128(0))
+        );
+        assert_eq!(
+            DataTypeValue::U128(u128::MAX) - DataTypeValue::I8(1), Err(ArithmeticError::Overflow)
+        );
+        assert_eq!(
+            DataTypeValue::I8(0) - DataTypeValue::U128(1 << 127), Ok(DataTypeValue::I128(i128::MIN))
+        );
+        assert_eq!(
+            DataTypeValue::U128(1) / DataTypeValue::I8(0), Err(ArithmeticError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn aggregations() {
+        let values = vec![DataTypeValue::U8(200), DataTypeValue::U8(100), DataTypeValue::I8(-3)];
+        assert_eq!(sum(&values), Ok(DataTypeValue::I64(297)));
+        assert_eq!(mean(&values), Ok(DataTypeValue::F64(99.0)));
+        assert_eq!(min(&values), Ok(DataTypeValue::I8(-3)));
+        assert_eq!(max(&values), Ok(DataTypeValue::U8(200)));
+        assert_eq!(
+            variance(vec![DataTypeValue::F32(1.0), DataTypeValue::U64(3)]),
+            Ok(DataTypeValue::F64(2.0))
+        );
+        assert_eq!(
+            variance(&values[..1]),
+            Err(ArithmeticError::NotEnoughValues { required: 2, found: 1 })
+        );
+        assert!(matches!(
+            min(vec![DataTypeValue::U8(1), DataTypeValue::String("a".to_string())]),
+            Err(ArithmeticError::Incomparable(_, _))
+        ));
+        assert_eq!(
+            sum(vec![DataTypeValue::U128(u128::MAX), DataTypeValue::U8(1)]),
+            Err(ArithmeticError::Overflow)
+        );
+        assert_eq!(
+            sum(vec![DataTypeValue::F64(f64::MAX), DataTypeValue::F64(f64::MAX)]),
+            Err(ArithmeticError::Overflow)
+        );
+        assert_eq!(
+            sum(vec![DataTypeValue::F32(f32::MAX), DataTypeValue::F32(f32::MAX)]),
+            Err(ArithmeticError::Overflow)
+        );
+        assert_eq!(
+            sum(vec![DataTypeValue::F64(f64::INFINITY), DataTypeValue::F64(1.0)]),
+            Ok(DataTypeValue::F64(f64::INFINITY))
+        );
+    }
+}
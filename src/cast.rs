@@ -0,0 +1,427 @@
+use std::{
+    mem,
+    fmt::{ Display, Formatter, Result as FmtResult }
+};
+
+use crate::{
+    data::{ DataType, DataTypeValue, DataDeductor },
+    numeric::Numeric,
+    temporal::{ Datetime, Duration, TimeUnit }
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum CastMode {
+    // fails unless the value is represented exactly in the target type
+    #[default]
+    Checked,
+    // clamps to the target range, truncates fractions and rounds to the nearest float
+    Saturating,
+    // behaves like the `as` operator, wrapping integers and mapping NaN to 0
+    Lossy
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CastErrorKind {
+    Unsupported,
+    OutOfRange,
+    Inexact,
+    NaN,
+    Unparsable
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CastError {
+    pub value: String,
+    pub to: String,
+    pub kind: CastErrorKind
+}
+
+impl CastError {
+    fn new(value: &DataTypeValue, to: &DataType, kind: CastErrorKind) -> CastError {
+        CastError { value: value.to_string(), to: to.to_string(), kind }
+    }
+}
+
+impl Display for CastError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let reason = match self.kind {
+            CastErrorKind::Unsupported => "unsupported conversion",
+            CastErrorKind::OutOfRange => "value out of the target range",
+            CastErrorKind::Inexact => "value not exactly representable",
+            CastErrorKind::NaN => "NaN has no representation",
+            CastErrorKind::Unparsable => "string cannot be parsed"
+        };
+        write!(f, "cannot cast {} to {}: {}", self.value, self.to, reason)
+    }
+}
+
+impl std::error::Error for CastError {}
+
+fn bool_to_numeric(v: bool) -> Numeric { Numeric::Unsigned(v as u128) }
+
+fn parse_numeric(s: &str) -> Option<Numeric> {
+    let s = s.trim();
+    if let Ok(v) = s.parse::<i128>() { return Some(Numeric::Signed(v)) }
+    if let Ok(v) = s.parse::<u128>() { return Some(Numeric::Unsigned(v)) }
+    if let Ok(v) = s.parse::<f64>() { return Some(Numeric::Float(v)) }
+    s.parse::<bool>().ok().map(bool_to_numeric)
+}
+
+fn is_infinity_literal(s: &str) -> bool {
+    let s = s.trim_start_matches(['+', '-']);
+    s.eq_ignore_ascii_case("inf") || s.eq_ignore_ascii_case("infinity")
+}
+
+fn cast_numeric(
+    number: Numeric, to: &DataType, mode: CastMode
+) -> Result<DataTypeValue, CastErrorKind> {
+    macro_rules! integer {
+        ($variant:ident, $t:ty) => {{
+            match mode {
+                CastMode::Lossy => match number {
+                    Numeric::Signed(v) => DataTypeValue::$variant(v as $t),
+                    Numeric::Unsigned(v) => DataTypeValue::$variant(v as $t),
+                    Numeric::Float(v) => DataTypeValue::$variant(v as $t)
+                },
+                CastMode::Checked | CastMode::Saturating => {
+                    let number = match number {
+                        Numeric::Float(v) if v.is_nan() => return Err(CastErrorKind::NaN),
+                        Numeric::Float(v) if v.fract() != 0.0 => {
+                            if mode == CastMode::Checked { return Err(CastErrorKind::Inexact) }
+                            Numeric::Float(v.trunc())
+                        }
+                        number => number
+                    };
+                    let min = DataTypeValue::$variant(<$t>::MIN);
+                    let max = DataTypeValue::$variant(<$t>::MAX);
+                    if number < min.to_numeric().unwrap() {
+                        if mode == CastMode::Checked { return Err(CastErrorKind::OutOfRange) }
+                        min
+                    } else if number > max.to_numeric().unwrap() {
+                        if mode == CastMode::Checked { return Err(CastErrorKind::OutOfRange) }
+                        max
+                    } else {
+                        number.to_value(to).ok_or(CastErrorKind::OutOfRange)?
+                    }
+                }
+            }
+        }}
+    }
+
+    let value = match to {
+        DataType::Bool => {
+            let is_zero = number == Numeric::Unsigned(0);
+            match mode {
+                _ if number.is_nan() && mode != CastMode::Lossy => return Err(CastErrorKind::NaN),
+                CastMode::Checked if !is_zero && number != Numeric::Unsigned(1) => {
+                    return Err(CastErrorKind::OutOfRange)
+                }
+                _ => DataTypeValue::Bool(!is_zero)
+            }
+        }
+        DataType::U8 => integer!(U8, u8),
+        DataType::U16 => integer!(U16, u16),
+        DataType::U32 => integer!(U32, u32),
+        DataType::U64 => integer!(U64, u64),
+        DataType::U128 => integer!(U128, u128),
+        DataType::USize => integer!(USize, usize),
+        DataType::I8 => integer!(I8, i8),
+        DataType::I16 => integer!(I16, i16),
+        DataType::I32 => integer!(I32, i32),
+        DataType::I64 => integer!(I64, i64),
+        DataType::I128 => integer!(I128, i128),
+        DataType::ISize => integer!(ISize, isize),
+        DataType::F32 => {
+            let v = number.to_f64();
+            if mode == CastMode::Checked && v.is_finite() && v.abs() > f32::MAX as f64 {
+                return Err(CastErrorKind::OutOfRange)
+            }
+            let result = match mode {
+                CastMode::Saturating if v.is_finite() => {
+                    v.clamp(f32::MIN as f64, f32::MAX as f64) as f32
+                }
+                _ => v as f32
+            };
+            if mode == CastMode::Checked
+                && !result.is_nan() && Numeric::Float(result as f64) != number {
+                return Err(CastErrorKind::Inexact)
+            }
+            DataTypeValue::F32(result)
+        }
+        DataType::F64 => {
+            let result = number.to_f64();
+            if mode == CastMode::Checked
+                && !result.is_nan() && Numeric::Float(result) != number {
+                return Err(CastErrorKind::Inexact)
+            }
+            DataTypeValue::F64(result)
+        }
+        _ => return Err(CastErrorKind::Unsupported)
+    };
+    Ok(value)
+}
+
+fn rescale(
+    value: i64, from: TimeUnit, to: TimeUnit, mode: CastMode
+) -> Result<i64, CastErrorKind> {
+    let nanoseconds = value as i128 * from.nanoseconds() as i128;
+    let unit = to.nanoseconds() as i128;
+    if mode == CastMode::Checked && nanoseconds % unit != 0 { return Err(CastErrorKind::Inexact) }
+    let rescaled = nanoseconds.div_euclid(unit);
+    match mode {
+        CastMode::Checked => i64::try_from(rescaled).map_err(|_| CastErrorKind::OutOfRange),
+        CastMode::Saturating => Ok(rescaled.clamp(i64::MIN as i128, i64::MAX as i128) as i64),
+        CastMode::Lossy => Ok(rescaled as i64)
+    }
+}
+
+fn to_text(value: &DataTypeValue) -> Option<String> {
+    let text = match value {
+        DataTypeValue::Bool(v) => v.to_string(),
+        DataTypeValue::U8(v) => v.to_string(),
+        DataTypeValue::U16(v) => v.to_string(),
+        DataTypeValue::U32(v) => v.to_string(),
+        DataTypeValue::U64(v) => v.to_string(),
+        DataTypeValue::U128(v) => v.to_string(),
+        DataTypeValue::USize(v) => v.to_string(),
+        DataTypeValue::I8(v) => v.to_string(),
+        DataTypeValue::I16(v) => v.to_string(),
+        DataTypeValue::I32(v) => v.to_string(),
+        DataTypeValue::I64(v) => v.to_string(),
+        DataTypeValue::I128(v) => v.to_string(),
+        DataTypeValue::ISize(v) => v.to_string(),
+        DataTypeValue::F32(v) => v.to_string(),
+        DataTypeValue::F64(v) => v.to_string(),
+        DataTypeValue::RcStr(v) => v.to_string(),
+        DataTypeValue::String(v) => v.clone(),
+        DataTypeValue::Date(v) => v.to_string(),
+        DataTypeValue::Datetime(v) => v.to_string(),
+        DataTypeValue::Duration(v) => v.to_string(),
+        DataTypeValue::Ordinal(v) => v.to_string(),
+        DataTypeValue::Unknown => return None
+    };
+    Some(text)
+}
+
+pub fn cast_value(
+    value: &DataTypeValue, to: &DataType, mode: CastMode
+) -> Result<DataTypeValue, CastError> {
+    let error = |kind| CastError::new(value, to, kind);
+
+    match to {
+        DataType::String => {
+            return to_text(value).map(DataTypeValue::String)
+                .ok_or_else(|| error(CastErrorKind::Unsupported))
+        }
+        DataType::RcStr => {
+            return to_text(value).map(|v| DataTypeValue::RcStr(v.into()))
+                .ok_or_else(|| error(CastErrorKind::Unsupported))
+        }
+        _ => {}
+    }
+
+    if mem::discriminant(&value.data_type()) == mem::discriminant(to) {
+        return match (value, to) {
+            (DataTypeValue::Datetime(v), DataType::Datetime(unit)) => {
+                let timestamp = rescale(v.timestamp, v.unit, *unit, mode).map_err(error)?;
+                Ok(DataTypeValue::Datetime(Datetime::new(timestamp, *unit, v.timezone.clone())))
+            }
+            (DataTypeValue::Duration(v), DataType::Duration(unit)) => {
+                let value = rescale(v.value, v.unit, *unit, mode).map_err(error)?;
+                Ok(DataTypeValue::Duration(Duration::new(value, *unit)))
+            }
+            _ => Ok(value.clone())
+        }
+    }
+
+    let number = match value {
+        DataTypeValue::Bool(v) => bool_to_numeric(*v),
+        DataTypeValue::RcStr(_) | DataTypeValue::String(_) => {
+            let text = match value {
+                DataTypeValue::RcStr(v) => v.trim(),
+                _ => value.as_string().unwrap().trim()
+            };
+            let number = match parse_numeric(text) {
+                // decimal literals are rounded once, straight to f32
+                Some(Numeric::Float(_)) if *to == DataType::F32 => match text.parse::<f32>() {
+                    Ok(v) => Numeric::Float(v as f64),
+                    Err(_) => return Err(error(CastErrorKind::Unparsable))
+                },
+                Some(number) => number,
+                None => return Err(error(CastErrorKind::Unparsable))
+            };
+            match number {
+                // finite literal beyond the range of the float it was parsed into
+                Numeric::Float(v) if v.is_infinite() && !is_infinity_literal(text) => match mode {
+                    CastMode::Checked => return Err(error(CastErrorKind::OutOfRange)),
+                    CastMode::Saturating => Numeric::Float(f64::MAX.copysign(v)),
+                    CastMode::Lossy => number
+                },
+                number => number
+            }
+        }
+        _ => match value.to_numeric() {
+            Some(number) => number,
+            None => return Err(error(CastErrorKind::Unsupported))
+        }
+    };
+
+    cast_numeric(number, to, mode).map_err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{ CastMode, CastErrorKind };
+    use crate::{
+        data::{ DataType, DataTypeValue },
+        temporal::{ Datetime, TimeUnit },
+        polars::DataVec
+    };
+
+    fn error_kind(value: DataTypeValue, data_type: DataType, mode: CastMode) -> CastErrorKind {
+        value.cast(data_type, mode).unwrap_err().kind
+    }
+
+    #[test]
+    fn numeric_modes() {
+        let v = DataTypeValue::I64(300);
+        assert_eq!(v.cast(DataType::U16, CastMode::Checked), Ok(DataTypeValue::U16(300)));
+        assert_eq!(error_kind(v.clone(), DataType::U8, CastMode::Checked), CastErrorKind::OutOfRange);
+        assert_eq!(v.cast(DataType::U8, CastMode::Saturating), Ok(DataTypeValue::U8(255)));
+        assert_eq!(v.cast(DataType::U8, CastMode::Lossy), Ok(DataTypeValue::U8(44)));
+        assert_eq!(
+            DataTypeValue::I8(-5).cast(DataType::U128, CastMode::Saturating),
+            Ok(DataTypeValue::U128(0))
+        );
+
+        let f = DataTypeValue::F64(-2.5);
+        assert_eq!(error_kind(f.clone(), DataType::I32, CastMode::Checked), CastErrorKind::Inexact);
+        assert_eq!(f.cast(DataType::I32, CastMode::Saturating), Ok(DataTypeValue::I32(-2)));
+        assert_eq!(f.cast(DataType::F32, CastMode::Checked), Ok(DataTypeValue::F32(-2.5)));
+        assert_eq!(
+            error_kind(DataTypeValue::F64(0.1), DataType::F32, CastMode::Checked),
+            CastErrorKind::Inexact
+        );
+        assert_eq!(
+            error_kind(DataTypeValue::F64(-1e300), DataType::F32, CastMode::Checked),
+            CastErrorKind::OutOfRange
+        );
+        assert_eq!(
+            error_kind(DataTypeValue::U128(u128::MAX), DataType::F32, CastMode::Checked),
+            CastErrorKind::OutOfRange
+        );
+        assert_eq!(
+            DataTypeValue::F64(1e300).cast(DataType::F32, CastMode::Saturating),
+            Ok(DataTypeValue::F32(f32::MAX))
+        );
+        assert_eq!(
+            error_kind(DataTypeValue::U64(u64::MAX), DataType::F64, CastMode::Checked),
+            CastErrorKind::Inexact
+        );
+        assert_eq!(
+            error_kind(DataTypeValue::F64(f64::NAN), DataType::I8, CastMode::Saturating),
+            CastErrorKind::NaN
+        );
+        assert_eq!(
+            DataTypeValue::F64(f64::NAN).cast(DataType::I8, CastMode::Lossy),
+            Ok(DataTypeValue::I8(0))
+        );
+    }
+
+    #[test]
+    fn bool_and_string() {
+        assert_eq!(
+            DataTypeValue::Bool(true).cast(DataType::F64, CastMode::Checked),
+            Ok(DataTypeValue::F64(1.0))
+        );
+        assert_eq!(
+            DataTypeValue::U8(1).cast(DataType::Bool, CastMode::Checked),
+            Ok(DataTypeValue::Bool(true))
+        );
+        assert_eq!(
+            error_kind(DataTypeValue::U8(2), DataType::Bool, CastMode::Checked),
+            CastErrorKind::OutOfRange
+        );
+        assert_eq!(
+            DataTypeValue::U8(2).cast(DataType::Bool, CastMode::Lossy),
+            Ok(DataTypeValue::Bool(true))
+        );
+
+        let text = |s: &str| DataTypeValue::String(s.to_string());
+        assert_eq!(text("0.1").cast(DataType::F32, CastMode::Checked), Ok(DataTypeValue::F32(0.1)));
+        assert_eq!(
+            error_kind(text("1e39"), DataType::F32, CastMode::Checked), CastErrorKind::OutOfRange
+        );
+        assert_eq!(
+            error_kind(text("1e400"), DataType::F64, CastMode::Checked), CastErrorKind::OutOfRange
+        );
+        assert_eq!(
+            text("-1e39").cast(DataType::F32, CastMode::Saturating),
+            Ok(DataTypeValue::F32(f32::MIN))
+        );
+        assert_eq!(
+            text("1e400").cast(DataType::F64, CastMode::Lossy),
+            Ok(DataTypeValue::F64(f64::INFINITY))
+        );
+        assert_eq!(
+            text("-inf").cast(DataType::F32, CastMode::Checked),
+            Ok(DataTypeValue::F32(f32::NEG_INFINITY))
+        );
+        assert_eq!(
+            error_kind(text("16777217"), DataType::F32, CastMode::Checked), CastErrorKind::Inexact
+        );
+
+        let s = DataTypeValue::String(" 2.75 ".to_string());
+        assert_eq!(s.cast(DataType::F64, CastMode::Checked), Ok(DataTypeValue::F64(2.75)));
+        assert_eq!(s.cast(DataType::U8, CastMode::Saturating), Ok(DataTypeValue::U8(2)));
+        assert_eq!(
+            error_kind(DataTypeValue::String("x".to_string()), DataType::I32, CastMode::Lossy),
+            CastErrorKind::Unparsable
+        );
+        assert_eq!(
+            DataTypeValue::I16(-7).cast(DataType::RcStr, CastMode::Checked),
+            Ok(DataTypeValue::RcStr("-7".into()))
+        );
+        assert_eq!(
+            DataTypeValue::String("1".to_string())
+                .cast(DataType::Date, CastMode::Lossy).unwrap_err().to_string(),
+            "cannot cast String(\"1\") to Date: unsupported conversion"
+        );
+    }
+
+    #[test]
+    fn temporal_units() {
+        let v = DataTypeValue::Datetime(Datetime::new(1_500, TimeUnit::Microseconds, None));
+        assert_eq!(
+            error_kind(v.clone(), DataType::Datetime(TimeUnit::Milliseconds), CastMode::Checked),
+            CastErrorKind::Inexact
+        );
+        assert_eq!(
+            v.cast(DataType::Datetime(TimeUnit::Milliseconds), CastMode::Lossy),
+            Ok(DataTypeValue::Datetime(Datetime::new(1, TimeUnit::Milliseconds, None)))
+        );
+    }
+
+    #[test]
+    fn columns() {
+        let column = DataVec::Int64Vec(vec![1, 300, -2].into());
+        assert!(column.cast(DataType::U8, CastMode::Checked).is_err());
+        assert_eq!(
+            column.cast(DataType::U8, CastMode::Saturating).unwrap(),
+            DataVec::UInt8Vec(vec![1, 255, 0].into())
+        );
+        assert_eq!(
+            column.cast(DataType::U128, CastMode::Saturating).unwrap(),
+            DataVec::UInt128Vec(vec![1, 300, 0].into())
+        );
+        assert!(column.cast(DataType::Ordinal, CastMode::Lossy).is_err());
+
+        let column = DataVec::Utf8Vec(vec![Some("1.5".to_string()), None].into());
+        assert_eq!(
+            column.cast(DataType::F64, CastMode::Checked).unwrap(),
+            DataVec::Float64Vec(vec![Some(1.5), None].into())
+        );
+    }
+}
@@ -0,0 +1,563 @@
+use std::{
+    rc::Rc,
+    io::{ self, Read, Write },
+    fmt::{ Display, Formatter, Result as FmtResult }
+};
+
+use crate::{
+    data::{ DataType, DataTypeValue, DataDeductor },
+    temporal::{ Date, Datetime, Duration, TimeUnit },
+    ordinal::{ Ordinal, OrdinalScale },
+    polars::DataVec,
+    column::Bitmap
+};
+
+// value:  type tag, time unit for Datetime and Duration, payload
+// column: type tag, time unit, varint length, nullable flag,
+//         validity bitmap (lsb first, 1 = present) if nullable, payloads of present values
+// payloads: unsigned integers as LEB128 varints, signed as zigzag varints,
+//           floats as little-endian IEEE 754, strings as varint length and utf-8 bytes
+
+#[derive(Debug)]
+pub enum CodecError {
+    Io(io::Error),
+    InvalidTag(u8),
+    InvalidUtf8,
+    VarintOverflow,
+    InvalidValue(String)
+}
+
+impl Display for CodecError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            CodecError::Io(e) => write!(f, "{}", e),
+            CodecError::InvalidTag(tag) => write!(f, "invalid tag {}", tag),
+            CodecError::InvalidUtf8 => write!(f, "invalid utf-8 string"),
+            CodecError::VarintOverflow => write!(f, "varint out of range"),
+            CodecError::InvalidValue(v) => write!(f, "invalid value: {}", v)
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+impl From<io::Error> for CodecError {
+    fn from(e: io::Error) -> CodecError { CodecError::Io(e) }
+}
+
+type CodecResult<T> = Result<T, CodecError>;
+
+fn type_tag(data_type: &DataType) -> u8 {
+    match data_type {
+        DataType::Bool => 0,
+        DataType::U8 => 1,
+        DataType::U16 => 2,
+        DataType::U32 => 3,
+        DataType::U64 => 4,
+        DataType::U128 => 5,
+        DataType::USize => 6,
+        DataType::I8 => 7,
+        DataType::I16 => 8,
+        DataType::I32 => 9,
+        DataType::I64 => 10,
+        DataType::I128 => 11,
+        DataType::ISize => 12,
+        DataType::F32 => 13,
+        DataType::F64 => 14,
+        DataType::RcStr => 15,
+        DataType::String => 16,
+        DataType::Date => 17,
+        DataType::Datetime(_) => 18,
+        DataType::Duration(_) => 19,
+        DataType::Ordinal => 20,
+        DataType::Unknown => 21
+    }
+}
+
+fn unit_tag(unit: TimeUnit) -> u8 {
+    match unit {
+        TimeUnit::Nanoseconds => 0,
+        TimeUnit::Microseconds => 1,
+        TimeUnit::Milliseconds => 2
+    }
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> CodecResult<u8> {
+    let mut byte = [0u8];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn read_unit<R: Read>(reader: &mut R) -> CodecResult<TimeUnit> {
+    match read_u8(reader)? {
+        0 => Ok(TimeUnit::Nanoseconds),
+        1 => Ok(TimeUnit::Microseconds),
+        2 => Ok(TimeUnit::Milliseconds),
+        tag => Err(CodecError::InvalidTag(tag))
+    }
+}
+
+fn write_type<W: Write>(writer: &mut W, data_type: &DataType) -> CodecResult<()> {
+    writer.write_all(&[type_tag(data_type)])?;
+    match data_type {
+        DataType::Datetime(unit) | DataType::Duration(unit) => {
+            writer.write_all(&[unit_tag(*unit)])?
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// None on a clean end of the stream
+fn read_type<R: Read>(reader: &mut R) -> CodecResult<Option<DataType>> {
+    let mut tag = [0u8];
+    if reader.read(&mut tag)? == 0 { return Ok(None) }
+    let data_type = match tag[0] {
+        0 => DataType::Bool,
+        1 => DataType::U8,
+        2 => DataType::U16,
+        3 => DataType::U32,
+        4 => DataType::U64,
+        5 => DataType::U128,
+        6 => DataType::USize,
+        7 => DataType::I8,
+        8 => DataType::I16,
+        9 => DataType::I32,
+        10 => DataType::I64,
+        11 => DataType::I128,
+        12 => DataType::ISize,
+        13 => DataType::F32,
+        14 => DataType::F64,
+        15 => DataType::RcStr,
+        16 => DataType::String,
+        17 => DataType::Date,
+        18 => DataType::Datetime(read_unit(reader)?),
+        19 => DataType::Duration(read_unit(reader)?),
+        20 => DataType::Ordinal,
+        21 => DataType::Unknown,
+        tag => return Err(CodecError::InvalidTag(tag))
+    };
+    Ok(Some(data_type))
+}
+
+fn read_type_required<R: Read>(reader: &mut R) -> CodecResult<DataType> {
+    read_type(reader)?.ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof).into())
+}
+
+fn write_varint<W: Write>(writer: &mut W, mut v: u128) -> CodecResult<()> {
+    loop {
+        let byte = (v & 0x7f) as u8;
+        v >>= 7;
+        if v == 0 { return Ok(writer.write_all(&[byte])?) }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_varint<R: Read>(reader: &mut R) -> CodecResult<u128> {
+    let mut v = 0u128;
+    for shift in (0..128).step_by(7) {
+        let byte = read_u8(reader)?;
+        let bits = (byte & 0x7f) as u128;
+        if shift == 126 && bits > 0b11 { return Err(CodecError::VarintOverflow) }
+        v |= bits << shift;
+        if byte & 0x80 == 0 { return Ok(v) }
+    }
+    Err(CodecError::VarintOverflow)
+}
+
+fn write_signed<W: Write>(writer: &mut W, v: i128) -> CodecResult<()> {
+    write_varint(writer, ((v << 1) ^ (v >> 127)) as u128)
+}
+
+fn read_signed<R: Read>(reader: &mut R) -> CodecResult<i128> {
+    let v = read_varint(reader)?;
+    Ok((v >> 1) as i128 ^ -((v & 1) as i128))
+}
+
+fn read_unsigned_as<R: Read, T: TryFrom<u128>>(reader: &mut R) -> CodecResult<T> {
+    T::try_from(read_varint(reader)?).map_err(|_| CodecError::VarintOverflow)
+}
+
+fn read_signed_as<R: Read, T: TryFrom<i128>>(reader: &mut R) -> CodecResult<T> {
+    T::try_from(read_signed(reader)?).map_err(|_| CodecError::VarintOverflow)
+}
+
+fn write_str<W: Write>(writer: &mut W, s: &str) -> CodecResult<()> {
+    write_varint(writer, s.len() as u128)?;
+    Ok(writer.write_all(s.as_bytes())?)
+}
+
+fn read_string<R: Read>(reader: &mut R) -> CodecResult<String> {
+    let len: usize = read_unsigned_as(reader)?;
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() < len { return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()) }
+    String::from_utf8(bytes).map_err(|_| CodecError::InvalidUtf8)
+}
+
+fn write_payload<W: Write>(writer: &mut W, value: &DataTypeValue) -> CodecResult<()> {
+    match value {
+        DataTypeValue::Bool(v) => writer.write_all(&[*v as u8])?,
+        DataTypeValue::U8(v) => write_varint(writer, *v as u128)?,
+        DataTypeValue::U16(v) => write_varint(writer, *v as u128)?,
+        DataTypeValue::U32(v) => write_varint(writer, *v as u128)?,
+        DataTypeValue::U64(v) => write_varint(writer, *v as u128)?,
+        DataTypeValue::U128(v) => write_varint(writer, *v)?,
+        DataTypeValue::USize(v) => write_varint(writer, *v as u128)?,
+        DataTypeValue::I8(v) => write_signed(writer, *v as i128)?,
+        DataTypeValue::I16(v) => write_signed(writer, *v as i128)?,
+        DataTypeValue::I32(v) => write_signed(writer, *v as i128)?,
+        DataTypeValue::I64(v) => write_signed(writer, *v as i128)?,
+        DataTypeValue::I128(v) => write_signed(writer, *v)?,
+        DataTypeValue::ISize(v) => write_signed(writer, *v as i128)?,
+        DataTypeValue::F32(v) => writer.write_all(&v.to_le_bytes())?,
+        DataTypeValue::F64(v) => writer.write_all(&v.to_le_bytes())?,
+        DataTypeValue::RcStr(v) => write_str(writer, v)?,
+        DataTypeValue::String(v) => write_str(writer, v)?,
+        DataTypeValue::Date(v) => write_signed(writer, v.0 as i128)?,
+        DataTypeValue::Datetime(v) => {
+            write_signed(writer, v.timestamp as i128)?;
+            match &v.timezone {
+                Some(timezone) => {
+                    writer.write_all(&[1])?;
+                    write_str(writer, timezone)?
+                }
+                None => writer.write_all(&[0])?
+            }
+        }
+        DataTypeValue::Duration(v) => write_signed(writer, v.value as i128)?,
+        DataTypeValue::Ordinal(v) => {
+            let levels = v.scale().levels();
+            write_varint(writer, levels.len() as u128)?;
+            for level in levels { write_str(writer, level)? }
+            write_varint(writer, v.rank() as u128)?
+        }
+        DataTypeValue::Unknown => {}
+    }
+    Ok(())
+}
+
+fn read_payload<R: Read>(reader: &mut R, data_type: &DataType) -> CodecResult<DataTypeValue> {
+    let value = match data_type {
+        DataType::Bool => match read_u8(reader)? {
+            0 => DataTypeValue::Bool(false),
+            1 => DataTypeValue::Bool(true),
+            v => return Err(CodecError::InvalidValue(format!("bool {}", v)))
+        },
+        DataType::U8 => DataTypeValue::U8(read_unsigned_as(reader)?),
+        DataType::U16 => DataTypeValue::U16(read_unsigned_as(reader)?),
+        DataType::U32 => DataTypeValue::U32(read_unsigned_as(reader)?),
+        DataType::U64 => DataTypeValue::U64(read_unsigned_as(reader)?),
+        DataType::U128 => DataTypeValue::U128(read_varint(reader)?),
+        DataType::USize => DataTypeValue::USize(read_unsigned_as(reader)?),
+        DataType::I8 => DataTypeValue::I8(read_signed_as(reader)?),
+        DataType::I16 => DataTypeValue::I16(read_signed_as(reader)?),
+        DataType::I32 => DataTypeValue::I32(read_signed_as(reader)?),
+        DataType::I64 => DataTypeValue::I64(read_signed_as(reader)?),
+        DataType::I128 => DataTypeValue::I128(read_signed(reader)?),
+        DataType::ISize => DataTypeValue::ISize(read_signed_as(reader)?),
+        DataType::F32 => {
+            let mut bytes = [0u8; 4];
+            reader.read_exact(&mut bytes)?;
+            DataTypeValue::F32(f32::from_le_bytes(bytes))
+        }
+        DataType::F64 => {
+            let mut bytes = [0u8; 8];
+            reader.read_exact(&mut bytes)?;
+            DataTypeValue::F64(f64::from_le_bytes(bytes))
+        }
+        DataType::RcStr => DataTypeValue::RcStr(Rc::from(read_string(reader)?)),
+        DataType::String => DataTypeValue::String(read_string(reader)?),
+        DataType::Date => DataTypeValue::Date(Date(read_signed_as(reader)?)),
+        DataType::Datetime(unit) => {
+            let timestamp = read_signed_as(reader)?;
+            let timezone = match read_u8(reader)? {
+                0 => None,
+                1 => Some(Rc::from(read_string(reader)?)),
+                v => return Err(CodecError::InvalidValue(format!("timezone flag {}", v)))
+            };
+            DataTypeValue::Datetime(Datetime::new(timestamp, *unit, timezone))
+        }
+        DataType::Duration(unit) => {
+            DataTypeValue::Duration(Duration::new(read_signed_as(reader)?, *unit))
+        }
+        DataType::Ordinal => {
+            let len: usize = read_unsigned_as(reader)?;
+            let levels = (0..len).map(|_| read_string(reader)).collect::<CodecResult<Vec<_>>>()?;
+            let levels: Vec<&str> = levels.iter().map(|level| level.as_str()).collect();
+            let scale = OrdinalScale::new(&levels).map_err(CodecError::InvalidValue)?;
+            let rank = read_unsigned_as(reader)?;
+            let ordinal = Ordinal::from_rank(&scale, rank).ok_or_else(|| {
+                CodecError::InvalidValue(format!("ordinal rank {}", rank))
+            })?;
+            DataTypeValue::Ordinal(ordinal)
+        }
+        DataType::Unknown => DataTypeValue::Unknown
+    };
+    Ok(value)
+}
+
+pub fn write_value<W: Write>(writer: &mut W, value: &DataTypeValue) -> CodecResult<()> {
+    write_type(writer, &value.data_type())?;
+    write_payload(writer, value)
+}
+
+pub fn read_value<R: Read>(reader: &mut R) -> CodecResult<DataTypeValue> {
+    let data_type = read_type_required(reader)?;
+    read_payload(reader, &data_type)
+}
+
+pub fn write_datavec<W: Write>(writer: &mut W, column: &DataVec) -> CodecResult<()> {
+    let data_type = column.data_type();
+    if let Some(v) = column.iter_valid().find(|v| v.data_type() != data_type) {
+        return Err(CodecError::InvalidValue(format!("{} in {} column", v, data_type)))
+    }
+
+    write_type(writer, &data_type)?;
+    write_varint(writer, column.len() as u128)?;
+    match column.validity() {
+        Some(validity) if column.null_count() > 0 => {
+            writer.write_all(&[1])?;
+            writer.write_all(validity.as_bytes())?;
+        }
+        _ => writer.write_all(&[0])?
+    }
+    for value in column.iter_valid() { write_payload(writer, &value)? }
+    Ok(())
+}
+
+pub fn read_datavec<R: Read>(reader: R) -> CodecResult<DataVec> {
+    let column = ColumnReader::new(reader)?;
+    let data_type = column.data_type();
+    let values = column.collect::<CodecResult<Vec<_>>>()?;
+    if data_type == DataType::Unknown { return Ok(DataVec::Unknown) }
+    DataVec::from_values(values, &data_type)
+        .ok_or_else(|| CodecError::InvalidValue(format!("{} column", data_type)))
+}
+
+// reads a column header and then decodes one value per iteration
+pub struct ColumnReader<R: Read> {
+    reader: R,
+    data_type: DataType,
+    len: usize,
+    position: usize,
+    validity: Option<Bitmap>
+}
+
+impl<R: Read> ColumnReader<R> {
+    pub fn new(mut reader: R) -> CodecResult<ColumnReader<R>> {
+        let data_type = read_type_required(&mut reader)?;
+        let len: usize = read_unsigned_as(&mut reader)?;
+        let validity = match read_u8(&mut reader)? {
+            0 => None,
+            1 => {
+                // the length is untrusted, so nothing is allocated ahead of the bytes read
+                let mut validity = Vec::new();
+                reader.by_ref().take(len.div_ceil(8) as u64).read_to_end(&mut validity)?;
+                if validity.len() < len.div_ceil(8) {
+                    return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into())
+                }
+                Bitmap::from_bytes(validity, len)
+            }
+            v => return Err(CodecError::InvalidValue(format!("nullable flag {}", v)))
+        };
+        Ok(ColumnReader { reader, data_type, len, position: 0, validity })
+    }
+
+    pub fn data_type(&self) -> DataType { self.data_type }
+
+    pub fn len(&self) -> usize { self.len }
+
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+}
+
+impl<R: Read> Iterator for ColumnReader<R> {
+    type Item = CodecResult<Option<DataTypeValue>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.len { return None }
+        let i = self.position;
+        self.position += 1;
+
+        if let Some(validity) = &self.validity {
+            if !validity.get(i) { return Some(Ok(None)) }
+        }
+        let value = read_payload(&mut self.reader, &self.data_type);
+        if value.is_err() { self.position = self.len }
+        Some(value.map(Some))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.position;
+        (remaining, Some(remaining))
+    }
+}
+
+// decodes consecutive tagged values until the end of the stream
+pub struct ValueReader<R: Read> {
+    reader: R,
+    done: bool
+}
+
+impl<R: Read> ValueReader<R> {
+    pub fn new(reader: R) -> ValueReader<R> { ValueReader { reader, done: false } }
+}
+
+impl<R: Read> Iterator for ValueReader<R> {
+    type Item = CodecResult<DataTypeValue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done { return None }
+        let value = match read_type(&mut self.reader) {
+            Ok(Some(data_type)) => read_payload(&mut self.reader, &data_type),
+            Ok(None) => { self.done = true; return None }
+            Err(e) => Err(e)
+        };
+        if value.is_err() { self.done = true }
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{
+        write_value, read_value, write_datavec, read_datavec,
+        ColumnReader, ValueReader, CodecError, CodecResult
+    };
+    use crate::{
+        data::{ DataType, DataTypeValue, DataDeductor },
+        temporal::{ Date, Datetime, Duration, TimeUnit },
+        ordinal::OrdinalScale,
+        polars::DataVec
+    };
+
+    fn round_trip(value: DataTypeValue) {
+        let mut bytes = Vec::new();
+        write_value(&mut bytes, &value).unwrap();
+        let decoded = read_value(&mut bytes.as_slice()).unwrap();
+        assert_eq!(decoded.to_string(), value.to_string());
+        assert_eq!(decoded.data_type(), value.data_type());
+    }
+
+    #[test]
+    fn value_round_trip() {
+        let scale = OrdinalScale::new(&["low", "mid", "high"]).unwrap();
+        for value in [
+            DataTypeValue::Bool(true),
+            DataTypeValue::U8(u8::MAX),
+            DataTypeValue::U16(300),
+            DataTypeValue::U32(u32::MAX),
+            DataTypeValue::U64(u64::MAX),
+            DataTypeValue::U128(u128::MAX),
+            DataTypeValue::USize(usize::MAX),
+            DataTypeValue::I8(i8::MIN),
+            DataTypeValue::I16(-300),
+            DataTypeValue::I32(i32::MIN),
+            DataTypeValue::I64(i64::MAX),
+            DataTypeValue::I128(i128::MIN),
+            DataTypeValue::ISize(isize::MIN),
+            DataTypeValue::F32(-1.5),
+            DataTypeValue::F64(f64::NAN),
+            DataTypeValue::RcStr(Rc::from("zażółć")),
+            DataTypeValue::String(String::new()),
+            DataTypeValue::Date(Date(-1)),
+            DataTypeValue::Datetime(Datetime::new(-5, TimeUnit::Nanoseconds, Some(Rc::from("Z")))),
+            DataTypeValue::Datetime(Datetime::new(1_000, TimeUnit::Microseconds, None)),
+            DataTypeValue::Duration(Duration::new(i64::MIN, TimeUnit::Milliseconds)),
+            DataTypeValue::Ordinal(scale.value("high").unwrap()),
+            DataTypeValue::Unknown
+        ] {
+            round_trip(value);
+        }
+    }
+
+    #[test]
+    fn compact_encoding() {
+        let mut bytes = Vec::new();
+        write_value(&mut bytes, &DataTypeValue::U64(5)).unwrap();
+        write_value(&mut bytes, &DataTypeValue::I32(-1)).unwrap();
+        write_value(&mut bytes, &DataTypeValue::String("ab".to_string())).unwrap();
+        assert_eq!(bytes, vec![4, 5, 9, 1, 16, 2, b'a', b'b']);
+
+        let values: Vec<DataTypeValue> = ValueReader::new(bytes.as_slice())
+            .collect::<CodecResult<_>>()
+            .unwrap();
+        assert_eq!(values, vec![
+            DataTypeValue::U64(5), DataTypeValue::I32(-1), DataTypeValue::String("ab".to_string())
+        ]);
+
+        assert!(matches!(read_value(&mut [4u8, 0x80].as_slice()), Err(CodecError::Io(_))));
+        let too_wide = read_value(&mut [1u8, 0x80, 0x02].as_slice());
+        assert!(matches!(too_wide, Err(CodecError::VarintOverflow)));
+        assert!(matches!(read_value(&mut [99u8].as_slice()), Err(CodecError::InvalidTag(99))));
+    }
+
+    #[test]
+    fn column_round_trip() {
+        let mut values = vec![None; 9];
+        values[0] = Some(1);
+        values[2] = Some(-3);
+        values[8] = Some(9);
+        let column = DataVec::Int64Vec(values.into());
+        let mut bytes = Vec::new();
+        write_datavec(&mut bytes, &column).unwrap();
+        assert_eq!(&bytes[..6], &[10, 9, 1, 0b0000_0101, 0b0000_0001, 2]);
+
+        let reader = ColumnReader::new(bytes.as_slice()).unwrap();
+        assert_eq!(reader.data_type(), DataType::I64);
+        assert_eq!(reader.len(), 9);
+        let values: Vec<Option<DataTypeValue>> = reader.collect::<CodecResult<_>>().unwrap();
+        assert_eq!(values, column.iter().collect::<Vec<_>>());
+        assert_eq!(read_datavec(bytes.as_slice()).unwrap(), column);
+
+        for column in [
+            DataVec::BoolVec(vec![true, false].into()),
+            DataVec::UInt8Vec(vec![0, u8::MAX].into()),
+            DataVec::UInt16Vec(vec![u16::MAX].into()),
+            DataVec::UInt32Vec(vec![7].into()),
+            DataVec::UInt64Vec(vec![u64::MAX].into()),
+            DataVec::UInt128Vec(vec![u128::MAX].into()),
+            DataVec::USizeVec(vec![usize::MAX].into()),
+            DataVec::Int8Vec(vec![i8::MIN].into()),
+            DataVec::Int16Vec(vec![-2].into()),
+            DataVec::Int32Vec(Vec::<i32>::new().into()),
+            DataVec::Int64Vec(vec![i64::MIN, i64::MAX].into()),
+            DataVec::Int128Vec(vec![Some(i128::MIN), None].into()),
+            DataVec::ISizeVec(vec![isize::MIN].into()),
+            DataVec::Float32Vec(vec![0.25].into()),
+            DataVec::Float64Vec(vec![-0.0, f64::INFINITY].into()),
+            DataVec::Utf8Vec(vec!["a".to_string(), "".to_string()].into()),
+            DataVec::RcStrVec(vec![Rc::from("b")].into()),
+            DataVec::DateVec(vec![Date(19_000)].into()),
+            DataVec::from_datetimes(
+                [Some(Datetime::new(1, TimeUnit::Microseconds, None))], TimeUnit::Microseconds
+            ),
+            DataVec::from_durations(
+                [Some(Duration::new(-1, TimeUnit::Nanoseconds))], TimeUnit::Nanoseconds
+            ),
+            DataVec::Unknown
+        ] {
+            let mut bytes = Vec::new();
+            write_datavec(&mut bytes, &column).unwrap();
+            let decoded = read_datavec(bytes.as_slice()).unwrap();
+            assert_eq!(decoded, column);
+            assert_eq!(decoded.data_type(), column.data_type());
+        }
+
+        let mixed = DataVec::DatetimeVec(vec![
+            Datetime::new(1, TimeUnit::Microseconds, None),
+            Datetime::new(1, TimeUnit::Nanoseconds, None)
+        ].into(), TimeUnit::Microseconds);
+        assert!(write_datavec(&mut Vec::new(), &mixed).is_err());
+
+        // a header claiming 2^61 values with a validity bitmap but no bytes behind it
+        let mut header = vec![10];
+        header.extend([0x80; 8]);
+        header.extend([0x20, 1]);
+        assert!(matches!(ColumnReader::new(header.as_slice()), Err(CodecError::Io(_))));
+    }
+}
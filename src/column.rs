@@ -0,0 +1,336 @@
+// packed booleans, least significant bit first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "BitmapFields"))]
+pub struct Bitmap {
+    bytes: Vec<u8>,
+    len: usize
+}
+
+// deserialized bitmaps go through Bitmap::from_bytes
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct BitmapFields {
+    bytes: Vec<u8>,
+    len: usize
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<BitmapFields> for Bitmap {
+    type Error = String;
+
+    fn try_from(fields: BitmapFields) -> Result<Bitmap, String> {
+        let bytes = fields.bytes.len();
+        Bitmap::from_bytes(fields.bytes, fields.len)
+            .ok_or_else(|| format!("{} bytes cannot hold {} bits", bytes, fields.len))
+    }
+}
+
+impl Bitmap {
+    pub fn new() -> Bitmap { Bitmap::default() }
+
+    pub fn filled(len: usize, value: bool) -> Bitmap {
+        let byte = if value { u8::MAX } else { 0 };
+        let mut bitmap = Bitmap { bytes: vec![byte; len.div_ceil(8)], len };
+        bitmap.clear_tail();
+        bitmap
+    }
+
+    // None if there are fewer bytes than the length requires
+    pub fn from_bytes(mut bytes: Vec<u8>, len: usize) -> Option<Bitmap> {
+        if bytes.len() < len.div_ceil(8) { return None }
+        bytes.truncate(len.div_ceil(8));
+        let mut bitmap = Bitmap { bytes, len };
+        bitmap.clear_tail();
+        Some(bitmap)
+    }
+
+    fn clear_tail(&mut self) {
+        if !self.len.is_multiple_of(8) {
+            if let Some(last) = self.bytes.last_mut() { *last &= (1 << (self.len % 8)) - 1 }
+        }
+    }
+
+    pub fn len(&self) -> usize { self.len }
+
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    pub fn as_bytes(&self) -> &[u8] { &self.bytes }
+
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.len, "bit {} out of {}", i, self.len);
+        self.bytes[i / 8] & (1 << (i % 8)) != 0
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) {
+        assert!(i < self.len, "bit {} out of {}", i, self.len);
+        if value { self.bytes[i / 8] |= 1 << (i % 8) } else { self.bytes[i / 8] &= !(1 << (i % 8)) }
+    }
+
+    pub fn push(&mut self, value: bool) {
+        if self.len.is_multiple_of(8) { self.bytes.push(0) }
+        self.len += 1;
+        self.set(self.len - 1, value);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.bytes.iter().map(|x| x.count_ones() as usize).sum()
+    }
+
+    pub fn count_zeros(&self) -> usize { self.len - self.count_ones() }
+
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ { (0..self.len).map(|i| self.get(i)) }
+}
+
+impl FromIterator<bool> for Bitmap {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Bitmap {
+        let mut bitmap = Bitmap::new();
+        for value in iter { bitmap.push(value) }
+        bitmap
+    }
+}
+
+// values buffer with an optional validity bitmap, null slots hold T::default(),
+// the bitmap is only allocated once a null shows up
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "ColumnFields<T>"))]
+pub struct Column<T> {
+    values: Vec<T>,
+    validity: Option<Bitmap>
+}
+
+// deserialized columns go through Column::with_validity
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct ColumnFields<T> {
+    values: Vec<T>,
+    validity: Option<Bitmap>
+}
+
+#[cfg(feature = "serde")]
+impl<T> TryFrom<ColumnFields<T>> for Column<T> {
+    type Error = String;
+
+    fn try_from(fields: ColumnFields<T>) -> Result<Column<T>, String> {
+        match fields.validity {
+            Some(validity) => {
+                let (values, bits) = (fields.values.len(), validity.len());
+                Column::with_validity(fields.values, validity)
+                    .ok_or_else(|| format!("{} validity bits for {} values", bits, values))
+            }
+            None => Ok(Column::new(fields.values))
+        }
+    }
+}
+
+impl<T> Column<T> {
+    pub fn new(values: Vec<T>) -> Column<T> { Column { values, validity: None } }
+
+    // None if the bitmap and values lengths differ
+    pub fn with_validity(values: Vec<T>, validity: Bitmap) -> Option<Column<T>> {
+        if values.len() != validity.len() { return None }
+        let validity = if validity.count_zeros() == 0 { None } else { Some(validity) };
+        Some(Column { values, validity })
+    }
+
+    pub fn len(&self) -> usize { self.values.len() }
+
+    pub fn is_empty(&self) -> bool { self.values.is_empty() }
+
+    pub fn null_count(&self) -> usize {
+        self.validity.as_ref().map(|x| x.count_zeros()).unwrap_or(0)
+    }
+
+    pub fn is_valid(&self, i: usize) -> bool {
+        i < self.len() && self.validity.as_ref().map(|x| x.get(i)).unwrap_or(true)
+    }
+
+    // None for nulls and out of range indices
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if self.is_valid(i) { Some(&self.values[i]) } else { None }
+    }
+
+    // raw buffer including the placeholders of nulls
+    pub fn values(&self) -> &[T] { &self.values }
+
+    pub fn validity(&self) -> Option<&Bitmap> { self.validity.as_ref() }
+
+    pub fn iter(&self) -> impl Iterator<Item = Option<&T>> + '_ {
+        (0..self.len()).map(|i| self.get(i))
+    }
+
+    pub fn iter_valid(&self) -> impl Iterator<Item = &T> + '_ { self.iter().flatten() }
+
+    pub fn into_values(self) -> Vec<T> { self.values }
+}
+
+impl<T: Default> Column<T> {
+    pub fn push(&mut self, value: Option<T>) {
+        match value {
+            Some(value) => {
+                if let Some(validity) = &mut self.validity { validity.push(true) }
+                self.values.push(value);
+            }
+            None => {
+                self.validity.get_or_insert_with(|| Bitmap::filled(self.values.len(), true))
+                    .push(false);
+                self.values.push(T::default());
+            }
+        }
+    }
+}
+
+impl<T: Clone> Column<T> {
+    pub fn drop_nulls(&self) -> Column<T> {
+        Column::new(self.iter_valid().cloned().collect())
+    }
+}
+
+impl<T> From<Vec<T>> for Column<T> {
+    fn from(values: Vec<T>) -> Column<T> { Column::new(values) }
+}
+
+impl<T: Default> From<Vec<Option<T>>> for Column<T> {
+    fn from(values: Vec<Option<T>>) -> Column<T> { values.into_iter().collect() }
+}
+
+impl<T: Default> FromIterator<Option<T>> for Column<T> {
+    fn from_iter<I: IntoIterator<Item = Option<T>>>(iter: I) -> Column<T> {
+        let iter = iter.into_iter();
+        let mut column = Column { values: Vec::with_capacity(iter.size_hint().0), validity: None };
+        for value in iter { column.push(value) }
+        column
+    }
+}
+
+impl<T> FromIterator<T> for Column<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Column<T> {
+        Column::new(iter.into_iter().collect())
+    }
+}
+
+// compares the logical values, the placeholders behind nulls are ignored
+impl<T: PartialEq> PartialEq for Column<T> {
+    fn eq(&self, other: &Column<T>) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::{ Series, NamedFrom };
+
+    use pretty_assertions::assert_eq;
+
+    use std::rc::Rc;
+
+    use super::{ Bitmap, Column };
+    use crate::{
+        data::{ DataType, DataTypeValue },
+        temporal::{ Datetime, TimeUnit },
+        polars::{ DataVec, series_to_datavec, series_to_datavec_skipna }
+    };
+
+    #[test]
+    fn bitmap() {
+        let mut bitmap: Bitmap = [true, false, true].into_iter().collect();
+        for _ in 0..7 { bitmap.push(true) }
+        assert_eq!(bitmap.len(), 10);
+        assert_eq!(bitmap.as_bytes(), &[0b1111_1101, 0b0000_0011]);
+        assert_eq!(bitmap.count_zeros(), 1);
+        bitmap.set(9, false);
+        assert!(!bitmap.get(9) && bitmap.get(8));
+        assert_eq!(Bitmap::filled(10, true).count_ones(), 10);
+        assert_eq!(Bitmap::from_bytes(vec![0xff, 0xff], 9).unwrap().as_bytes(), &[0xff, 1]);
+        assert_eq!(Bitmap::from_bytes(vec![0xff], 9), None);
+    }
+
+    #[test]
+    fn validity() {
+        let column: Column<i64> = vec![Some(1), None, Some(3)].into();
+        assert_eq!(column.len(), 3);
+        assert_eq!(column.null_count(), 1);
+        assert_eq!(column.get(1), None);
+        assert_eq!(column.get(2), Some(&3));
+        assert_eq!(column.get(3), None);
+        assert_eq!(column.iter_valid().copied().collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(column.drop_nulls(), Column::new(vec![1, 3]));
+
+        let dense: Column<i64> = vec![Some(1), Some(2)].into();
+        assert!(dense.validity().is_none());
+        assert_eq!(dense, Column::from(vec![1, 2]));
+
+        let validity: Bitmap = [true, false, true].into_iter().collect();
+        assert_eq!(Column::with_validity(vec![1, 7, 3], validity).unwrap(), column);
+        assert!(Column::with_validity(vec![1], Bitmap::filled(2, true)).is_none());
+    }
+
+    #[test]
+    fn datavec_views() {
+        let series = Series::new("age", &[Some(30i64), None, Some(41)]);
+        let column = series_to_datavec(&series).unwrap();
+        assert_eq!((column.len(), column.null_count()), (3, 1));
+        assert_eq!(column.get(0), Some(DataTypeValue::I64(30)));
+        assert_eq!(column.get(1), None);
+        assert_eq!(column.iter().collect::<Vec<_>>(), vec![
+            Some(DataTypeValue::I64(30)), None, Some(DataTypeValue::I64(41))
+        ]);
+        assert_eq!(column.iter_valid().count(), 2);
+        assert_eq!(column.as_i64().unwrap().values(), &[30, 0, 41]);
+        assert!(column.as_f64().is_none());
+
+        let skipna = series_to_datavec_skipna(&series).unwrap();
+        assert_eq!(skipna, DataVec::Int64Vec(vec![30, 41].into()));
+        assert!(skipna.validity().is_none());
+    }
+
+    #[test]
+    fn datavec_covers_data_types() {
+        let wide: DataVec = vec![u128::MAX, 1].into();
+        assert_eq!(wide.data_type(), DataType::U128);
+        assert_eq!(wide.get(0), Some(DataTypeValue::U128(u128::MAX)));
+
+        let sizes: DataVec = (0..3isize).map(|x| if x == 1 { None } else { Some(-x) }).collect();
+        assert_eq!(sizes.data_type(), DataType::ISize);
+        assert_eq!(sizes.null_count(), 1);
+
+        let names = DataVec::from(vec![Some(Rc::<str>::from("a")), None]);
+        assert_eq!(names.as_rc_str().unwrap().get(0).map(|x| &**x), Some("a"));
+
+        let datetimes = DataVec::from_datetimes(
+            [None, Some(Datetime::new(1, TimeUnit::Milliseconds, None))], TimeUnit::Microseconds
+        );
+        assert_eq!(datetimes.data_type(), DataType::Datetime(TimeUnit::Microseconds));
+        assert_eq!(datetimes.as_datetime().unwrap().get(1).map(|x| x.timestamp), Some(1_000));
+        let coarse = DataVec::from_datetimes(
+            [Some(Datetime::new(1_500, TimeUnit::Microseconds, None))], TimeUnit::Milliseconds
+        );
+        assert_eq!(coarse.null_count(), 1);
+        let empty = DataVec::from_durations([], TimeUnit::Nanoseconds);
+        assert_eq!(empty.data_type(), DataType::Duration(TimeUnit::Nanoseconds));
+
+        for data_type in [
+            DataType::Bool, DataType::U8, DataType::U16, DataType::U32, DataType::U64,
+            DataType::U128, DataType::USize, DataType::I8, DataType::I16, DataType::I32,
+            DataType::I64, DataType::I128, DataType::ISize, DataType::F32, DataType::F64,
+            DataType::RcStr, DataType::String, DataType::Date,
+            DataType::Datetime(TimeUnit::Milliseconds), DataType::Duration(TimeUnit::Milliseconds)
+        ] {
+            let column = DataVec::from_values(vec![None], &data_type).unwrap();
+            assert_eq!(column.data_type(), data_type);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_checks_lengths() {
+        let column: Column<i64> = vec![Some(1), None].into();
+        let json = serde_json::to_string(&column).unwrap();
+        assert_eq!(json, r#"{"values":[1,0],"validity":{"bytes":[1],"len":2}}"#);
+        assert_eq!(serde_json::from_str::<Column<i64>>(&json).unwrap(), column);
+        assert!(serde_json::from_str::<Bitmap>(r#"{"bytes":[],"len":9}"#).is_err());
+        let short = r#"{"values":[1],"validity":{"bytes":[1],"len":2}}"#;
+        assert!(serde_json::from_str::<Column<i64>>(short).is_err());
+    }
+}
@@ -12,12 +12,14 @@ use std::{
 use crate::neuron::{ Neuron, NeuronID };
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConnectionID {
     pub from: NeuronID,
     pub to: NeuronID
 }
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConnectionKind {
     Defining,
     Similarity,
@@ -1,18 +1,33 @@
 use std::{
     rc::Rc,
+    cmp::Ordering,
     marker::PhantomData,
     fmt::{ Display, Formatter, Result as FmtResult }
 };
 
 use enum_as_inner::EnumAsInner;
 
-use num_traits::ToPrimitive;
+use polars::prelude::DataType as PolarsDataType;
+
+pub use bionet_common_derive::DataDeductor;
 
 use crate::{
-    distances::Distance
+    distances::Distance,
+    temporal::{ Date, Datetime, Duration, TimeUnit },
+    ordinal::Ordinal,
+    geo::GeoPoint,
+    embedding::Embedding,
+    taxonomy::TaxonomyNode,
+    interval::Interval,
+    text::Text,
+    numeric::{ Numeric, PromotionMode },
+    inference::{ self, InferenceOptions, InferredColumn },
+    parsing::{ self, ParseOptions, ParseError },
+    cast::{ self, CastMode, CastError }
 };
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DataCategory {
     Numerical,
     Categorical,
@@ -47,15 +62,33 @@ macro_rules! impl_categorical {
     }
 }
 
+macro_rules! impl_ordinal {
+    ( $($t:ty),* ) => {
+        $( impl From<&$t> for DataCategory {
+            fn from(_data: &$t) -> DataCategory { DataCategory::Ordinal }
+        }
+        impl From<&[$t]> for DataCategory {
+            fn from(_data: &[$t]) -> DataCategory { DataCategory::Ordinal }
+        }
+        impl From<&[Option<$t>]> for DataCategory {
+            fn from(_data: &[Option<$t>]) -> DataCategory { DataCategory::Ordinal }
+        }) *
+    }
+}
+
 impl_numerical! { 
     i8, i16, i32, i64, i128, isize,
     u8, u16, u32, u64, u128, usize,
-    f32, f64
+    f32, f64,
+    Date, Datetime, Duration, GeoPoint, Embedding, Interval
 }
 
-impl_categorical! { String, Rc<str>, bool }
+impl_categorical! { String, Rc<str>, bool, TaxonomyNode, Text }
+
+impl_ordinal! { Ordinal }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DataType {
     Bool,
     U8,
@@ -74,10 +107,17 @@ pub enum DataType {
     F64,
     RcStr,
     String,
+    Date,
+    Datetime(TimeUnit),
+    Duration(TimeUnit),
+    Ordinal,
+    // also the type of values without a column or cast representation (GeoPoint, Embedding,
+    // TaxonomyNode, Interval, Text), their data_category is the one to dispatch on
     Unknown
 }
 
-#[derive(EnumAsInner, Clone, Debug, PartialEq, PartialOrd)]
+#[derive(EnumAsInner, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DataTypeValue {
     Bool(bool),
     U8(u8),
@@ -96,9 +136,173 @@ pub enum DataTypeValue {
     F64(f64),
     RcStr(Rc<str>),
     String(String),
+    Date(Date),
+    Datetime(Datetime),
+    Duration(Duration),
+    Ordinal(Ordinal),
     Unknown
 }
 
+impl DataType {
+    pub fn is_numeric(&self) -> bool { self.is_integer() || self.is_float() }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            DataType::U8 | DataType::U16 | DataType::U32 | DataType::U64 | DataType::U128
+                | DataType::USize | DataType::I8 | DataType::I16 | DataType::I32
+                | DataType::I64 | DataType::I128 | DataType::ISize
+        )
+    }
+
+    pub fn is_float(&self) -> bool { matches!(self, DataType::F32 | DataType::F64) }
+
+    pub fn is_signed(&self) -> bool {
+        matches!(
+            self,
+            DataType::I8 | DataType::I16 | DataType::I32 | DataType::I64 | DataType::I128
+                | DataType::ISize | DataType::F32 | DataType::F64
+        )
+    }
+
+    pub fn is_temporal(&self) -> bool {
+        matches!(self, DataType::Date | DataType::Datetime(_) | DataType::Duration(_))
+    }
+
+    pub fn is_textual(&self) -> bool { matches!(self, DataType::RcStr | DataType::String) }
+
+    pub fn bit_width(&self) -> Option<u32> {
+        let bits = match self {
+            DataType::Bool => 1,
+            DataType::U8 | DataType::I8 => 8,
+            DataType::U16 | DataType::I16 => 16,
+            DataType::U32 | DataType::I32 | DataType::F32 | DataType::Date => 32,
+            DataType::U64 | DataType::I64 | DataType::F64 => 64,
+            DataType::Datetime(_) | DataType::Duration(_) => 64,
+            DataType::U128 | DataType::I128 => 128,
+            DataType::USize => usize::BITS,
+            DataType::ISize => isize::BITS,
+            _ => return None
+        };
+        Some(bits)
+    }
+
+    pub fn min_value(&self) -> Option<DataTypeValue> {
+        let value = match self {
+            DataType::Bool => DataTypeValue::Bool(false),
+            DataType::U8 => DataTypeValue::U8(u8::MIN),
+            DataType::U16 => DataTypeValue::U16(u16::MIN),
+            DataType::U32 => DataTypeValue::U32(u32::MIN),
+            DataType::U64 => DataTypeValue::U64(u64::MIN),
+            DataType::U128 => DataTypeValue::U128(u128::MIN),
+            DataType::USize => DataTypeValue::USize(usize::MIN),
+            DataType::I8 => DataTypeValue::I8(i8::MIN),
+            DataType::I16 => DataTypeValue::I16(i16::MIN),
+            DataType::I32 => DataTypeValue::I32(i32::MIN),
+            DataType::I64 => DataTypeValue::I64(i64::MIN),
+            DataType::I128 => DataTypeValue::I128(i128::MIN),
+            DataType::ISize => DataTypeValue::ISize(isize::MIN),
+            DataType::F32 => DataTypeValue::F32(f32::MIN),
+            DataType::F64 => DataTypeValue::F64(f64::MIN),
+            DataType::Date => DataTypeValue::Date(Date(i32::MIN)),
+            DataType::Datetime(unit) => {
+                DataTypeValue::Datetime(Datetime::new(i64::MIN, *unit, None))
+            }
+            DataType::Duration(unit) => DataTypeValue::Duration(Duration::new(i64::MIN, *unit)),
+            _ => return None
+        };
+        Some(value)
+    }
+
+    pub fn max_value(&self) -> Option<DataTypeValue> {
+        let value = match self {
+            DataType::Bool => DataTypeValue::Bool(true),
+            DataType::U8 => DataTypeValue::U8(u8::MAX),
+            DataType::U16 => DataTypeValue::U16(u16::MAX),
+            DataType::U32 => DataTypeValue::U32(u32::MAX),
+            DataType::U64 => DataTypeValue::U64(u64::MAX),
+            DataType::U128 => DataTypeValue::U128(u128::MAX),
+            DataType::USize => DataTypeValue::USize(usize::MAX),
+            DataType::I8 => DataTypeValue::I8(i8::MAX),
+            DataType::I16 => DataTypeValue::I16(i16::MAX),
+            DataType::I32 => DataTypeValue::I32(i32::MAX),
+            DataType::I64 => DataTypeValue::I64(i64::MAX),
+            DataType::I128 => DataTypeValue::I128(i128::MAX),
+            DataType::ISize => DataTypeValue::ISize(isize::MAX),
+            DataType::F32 => DataTypeValue::F32(f32::MAX),
+            DataType::F64 => DataTypeValue::F64(f64::MAX),
+            DataType::Date => DataTypeValue::Date(Date(i32::MAX)),
+            DataType::Datetime(unit) => {
+                DataTypeValue::Datetime(Datetime::new(i64::MAX, *unit, None))
+            }
+            DataType::Duration(unit) => DataTypeValue::Duration(Duration::new(i64::MAX, *unit)),
+            _ => return None
+        };
+        Some(value)
+    }
+
+    pub fn data_category(&self) -> DataCategory {
+        match self {
+            DataType::Bool | DataType::RcStr | DataType::String | DataType::Unknown => {
+                DataCategory::Categorical
+            }
+            DataType::Ordinal => DataCategory::Ordinal,
+            _ => DataCategory::Numerical
+        }
+    }
+
+    pub fn to_polars(&self) -> Option<PolarsDataType> {
+        let data_type = match self {
+            DataType::Bool => PolarsDataType::Boolean,
+            DataType::U8 => PolarsDataType::UInt8,
+            DataType::U16 => PolarsDataType::UInt16,
+            DataType::U32 => PolarsDataType::UInt32,
+            DataType::U64 => PolarsDataType::UInt64,
+            DataType::USize if usize::BITS == 64 => PolarsDataType::UInt64,
+            DataType::USize => PolarsDataType::UInt32,
+            DataType::I8 => PolarsDataType::Int8,
+            DataType::I16 => PolarsDataType::Int16,
+            DataType::I32 => PolarsDataType::Int32,
+            DataType::I64 => PolarsDataType::Int64,
+            DataType::ISize if isize::BITS == 64 => PolarsDataType::Int64,
+            DataType::ISize => PolarsDataType::Int32,
+            DataType::F32 => PolarsDataType::Float32,
+            DataType::F64 => PolarsDataType::Float64,
+            DataType::RcStr | DataType::String => PolarsDataType::Utf8,
+            DataType::Date => PolarsDataType::Date,
+            DataType::Datetime(unit) => PolarsDataType::Datetime((*unit).into(), None),
+            DataType::Duration(unit) => PolarsDataType::Duration((*unit).into()),
+            DataType::U128 | DataType::I128 | DataType::Ordinal | DataType::Unknown => {
+                return None
+            }
+        };
+        Some(data_type)
+    }
+}
+
+impl From<&PolarsDataType> for DataType {
+    fn from(data_type: &PolarsDataType) -> DataType {
+        match data_type {
+            PolarsDataType::Boolean => DataType::Bool,
+            PolarsDataType::UInt8 => DataType::U8,
+            PolarsDataType::UInt16 => DataType::U16,
+            PolarsDataType::UInt32 => DataType::U32,
+            PolarsDataType::UInt64 => DataType::U64,
+            PolarsDataType::Int8 => DataType::I8,
+            PolarsDataType::Int16 => DataType::I16,
+            PolarsDataType::Int32 => DataType::I32,
+            PolarsDataType::Int64 => DataType::I64,
+            PolarsDataType::Float32 => DataType::F32,
+            PolarsDataType::Float64 => DataType::F64,
+            PolarsDataType::Utf8 => DataType::String,
+            PolarsDataType::Date => DataType::Date,
+            PolarsDataType::Datetime(unit, _) => DataType::Datetime((*unit).into()),
+            PolarsDataType::Duration(unit) => DataType::Duration((*unit).into()),
+            _ => DataType::Unknown
+        }
+    }
+}
+
 impl Display for DataType {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         write!(f, "{:?}", self)
@@ -111,16 +315,95 @@ impl Display for DataTypeValue {
     }
 }
 
-impl Distance for DataTypeValue {
-    fn distance(&self, v: &DataTypeValue) -> f64 {
-        fn numeric_distance<T: ToPrimitive>(lhs: &T, rhs: &T) -> f64 {
-            unsafe { 
-                let lhsv = ToPrimitive::to_f64(lhs).unwrap_unchecked();
-                let rhsv = ToPrimitive::to_f64(rhs).unwrap_unchecked();
-                (lhsv - rhsv).abs()
+impl DataTypeValue {
+    pub fn to_numeric(&self) -> Option<Numeric> { Numeric::from_value(self) }
+
+    pub fn cast(&self, data_type: DataType, mode: CastMode) -> Result<DataTypeValue, CastError> {
+        cast::cast_value(self, &data_type, mode)
+    }
+
+    fn variant_index(&self) -> usize {
+        match self {
+            DataTypeValue::Bool(_) => 0,
+            DataTypeValue::U8(_) => 1,
+            DataTypeValue::U16(_) => 2,
+            DataTypeValue::U32(_) => 3,
+            DataTypeValue::U64(_) => 4,
+            DataTypeValue::U128(_) => 5,
+            DataTypeValue::USize(_) => 6,
+            DataTypeValue::I8(_) => 7,
+            DataTypeValue::I16(_) => 8,
+            DataTypeValue::I32(_) => 9,
+            DataTypeValue::I64(_) => 10,
+            DataTypeValue::I128(_) => 11,
+            DataTypeValue::ISize(_) => 12,
+            DataTypeValue::F32(_) => 13,
+            DataTypeValue::F64(_) => 14,
+            DataTypeValue::RcStr(_) => 15,
+            DataTypeValue::String(_) => 16,
+            DataTypeValue::Date(_) => 17,
+            DataTypeValue::Datetime(_) => 18,
+            DataTypeValue::Duration(_) => 19,
+            DataTypeValue::Ordinal(_) => 20,
+            DataTypeValue::Unknown => 21
+        }
+    }
+
+    pub fn eq_with(&self, other: &DataTypeValue, mode: PromotionMode) -> bool {
+        self.partial_cmp_with(other, mode) == Some(Ordering::Equal)
+    }
+
+    pub fn partial_cmp_with(
+        &self, other: &DataTypeValue, mode: PromotionMode
+    ) -> Option<Ordering> {
+        if mode == PromotionMode::Promote {
+            if let (Some(lhs), Some(rhs)) = (self.to_numeric(), other.to_numeric()) {
+                return lhs.partial_cmp(&rhs)
             }
         }
 
+        match (self, other) {
+            (DataTypeValue::Bool(lhs), DataTypeValue::Bool(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::U8(lhs), DataTypeValue::U8(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::U16(lhs), DataTypeValue::U16(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::U32(lhs), DataTypeValue::U32(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::U64(lhs), DataTypeValue::U64(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::U128(lhs), DataTypeValue::U128(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::USize(lhs), DataTypeValue::USize(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::I8(lhs), DataTypeValue::I8(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::I16(lhs), DataTypeValue::I16(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::I32(lhs), DataTypeValue::I32(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::I64(lhs), DataTypeValue::I64(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::I128(lhs), DataTypeValue::I128(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::ISize(lhs), DataTypeValue::ISize(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::F32(lhs), DataTypeValue::F32(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::F64(lhs), DataTypeValue::F64(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::RcStr(lhs), DataTypeValue::RcStr(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::String(lhs), DataTypeValue::String(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::Date(lhs), DataTypeValue::Date(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::Datetime(lhs), DataTypeValue::Datetime(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::Duration(lhs), DataTypeValue::Duration(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::Ordinal(lhs), DataTypeValue::Ordinal(rhs)) => lhs.partial_cmp(rhs),
+            (DataTypeValue::Unknown, DataTypeValue::Unknown) => Some(Ordering::Equal),
+            _ => self.variant_index().partial_cmp(&other.variant_index())
+        }
+    }
+
+    // None unless both values are integers at most u128::MAX apart
+    pub fn exact_distance(&self, v: &DataTypeValue) -> Option<u128> {
+        self.to_numeric()?.exact_distance(&v.to_numeric()?)
+    }
+
+    pub fn distance_with(&self, v: &DataTypeValue, mode: PromotionMode) -> f64 {
+        if mode == PromotionMode::Promote {
+            if let (Some(lhs), Some(rhs)) = (self.to_numeric(), v.to_numeric()) {
+                return lhs.distance(&rhs)
+            }
+        }
+
+        // exact for integers, see distances::ExactDistance
+        fn numeric_distance<T: Distance>(lhs: &T, rhs: &T) -> f64 { lhs.distance(rhs) }
+
         match self {
             DataTypeValue::Bool(lhs) => {
                 let rhs = match v.as_bool() { Some(v) => v, None => return f64::NAN };
@@ -190,11 +473,43 @@ impl Distance for DataTypeValue {
                 let rhs = match v.as_string() { Some(v) => v, None => return f64::NAN };
                 if *lhs == *rhs { 0.0 } else { 1.0 }
             }
+            DataTypeValue::Date(lhs) => {
+                let rhs = match v.as_date() { Some(v) => v, None => return f64::NAN };
+                lhs.distance(rhs)
+            }
+            DataTypeValue::Datetime(lhs) => {
+                let rhs = match v.as_datetime() { Some(v) => v, None => return f64::NAN };
+                lhs.distance(rhs)
+            }
+            DataTypeValue::Duration(lhs) => {
+                let rhs = match v.as_duration() { Some(v) => v, None => return f64::NAN };
+                lhs.distance(rhs)
+            }
+            DataTypeValue::Ordinal(lhs) => {
+                let rhs = match v.as_ordinal() { Some(v) => v, None => return f64::NAN };
+                lhs.distance(rhs)
+            }
             DataTypeValue::Unknown => f64::NAN
         }
     }
 }
 
+impl PartialEq for DataTypeValue {
+    fn eq(&self, other: &DataTypeValue) -> bool { self.eq_with(other, PromotionMode::Promote) }
+}
+
+impl PartialOrd for DataTypeValue {
+    fn partial_cmp(&self, other: &DataTypeValue) -> Option<Ordering> {
+        self.partial_cmp_with(other, PromotionMode::Promote)
+    }
+}
+
+impl Distance for DataTypeValue {
+    fn distance(&self, v: &DataTypeValue) -> f64 {
+        self.distance_with(v, PromotionMode::Promote)
+    }
+}
+
 impl From<bool> for DataTypeValue { 
     fn from(v: bool) -> DataTypeValue { DataTypeValue::Bool(v) } 
 }
@@ -263,6 +578,22 @@ impl From<String> for DataTypeValue {
     fn from(v: String) -> DataTypeValue { DataTypeValue::String(v) } 
 }
 
+impl From<Date> for DataTypeValue { 
+    fn from(v: Date) -> DataTypeValue { DataTypeValue::Date(v) } 
+}
+
+impl From<Datetime> for DataTypeValue { 
+    fn from(v: Datetime) -> DataTypeValue { DataTypeValue::Datetime(v) } 
+}
+
+impl From<Duration> for DataTypeValue { 
+    fn from(v: Duration) -> DataTypeValue { DataTypeValue::Duration(v) } 
+}
+
+impl From<Ordinal> for DataTypeValue { 
+    fn from(v: Ordinal) -> DataTypeValue { DataTypeValue::Ordinal(v) } 
+}
+
 impl From<DataTypeValue> for Option<bool> { 
     fn from(v: DataTypeValue) -> Option<bool> { v.into_bool().ok() } 
 }
@@ -331,6 +662,22 @@ impl From<DataTypeValue> for Option<String> {
     fn from(v: DataTypeValue) -> Option<String> { v.into_string().ok() } 
 }
 
+impl From<DataTypeValue> for Option<Date> { 
+    fn from(v: DataTypeValue) -> Option<Date> { v.into_date().ok() } 
+}
+
+impl From<DataTypeValue> for Option<Datetime> { 
+    fn from(v: DataTypeValue) -> Option<Datetime> { v.into_datetime().ok() } 
+}
+
+impl From<DataTypeValue> for Option<Duration> { 
+    fn from(v: DataTypeValue) -> Option<Duration> { v.into_duration().ok() } 
+}
+
+impl From<DataTypeValue> for Option<Ordinal> { 
+    fn from(v: DataTypeValue) -> Option<Ordinal> { v.into_ordinal().ok() } 
+}
+
 pub struct DataTypeValueStr<'a>(pub &'a str);
 
 impl<'a> DataTypeValueStr<'a> {
@@ -353,14 +700,38 @@ impl<'a> DataTypeValueStr<'a> {
             DataType::F64 => DataTypeValue::F64(self.0.parse().ok()?),
             DataType::RcStr => DataTypeValue::RcStr(self.0.into()),
             DataType::String => DataTypeValue::String(self.0.parse().ok()?),
-            DataType::Unknown => return None
+            DataType::Date => DataTypeValue::Date(Date::parse(self.0)?),
+            DataType::Datetime(unit) => DataTypeValue::Datetime(Datetime::parse(self.0, unit)?),
+            DataType::Duration(unit) => DataTypeValue::Duration(Duration::parse(self.0, unit)?),
+            DataType::Ordinal | DataType::Unknown => return None
         };
         Some(result)
     }
+
+    pub fn data_type_value_with(
+        &self, data_type: DataType, options: &ParseOptions
+    ) -> Result<Option<DataTypeValue>, ParseError> {
+        parsing::parse_cell(self.0, data_type, options)
+    }
+
+    pub fn infer<S: AsRef<str>>(cells: &[S], options: &InferenceOptions) -> InferredColumn {
+        inference::infer_column(cells, options)
+    }
 }
 
 pub auto trait UnknownDataTypeMarker {}
 
+// negative UnknownDataTypeMarker impl for the DataDeductor derive, expanded with the
+// features of this crate so that deriving crates stay on stable syntax
+#[doc(hidden)]
+#[macro_export]
+#[allow_internal_unstable(negative_impls)]
+macro_rules! impl_known_data_type {
+    ( [$($impl_generics:tt)*] $t:ty, [$($where_clause:tt)*] ) => {
+        impl $($impl_generics)* !$crate::data::UnknownDataTypeMarker for $t $($where_clause)* {}
+    }
+}
+
 impl !UnknownDataTypeMarker for bool {}
 impl !UnknownDataTypeMarker for u8 {}
 impl !UnknownDataTypeMarker for u16 {}
@@ -378,6 +749,16 @@ impl !UnknownDataTypeMarker for f32 {}
 impl !UnknownDataTypeMarker for f64 {}
 impl !UnknownDataTypeMarker for Rc<str> {}
 impl !UnknownDataTypeMarker for String {}
+impl !UnknownDataTypeMarker for Date {}
+impl !UnknownDataTypeMarker for Datetime {}
+impl !UnknownDataTypeMarker for Duration {}
+impl !UnknownDataTypeMarker for Ordinal {}
+impl !UnknownDataTypeMarker for GeoPoint {}
+impl !UnknownDataTypeMarker for Embedding {}
+impl !UnknownDataTypeMarker for TaxonomyNode {}
+impl !UnknownDataTypeMarker for Interval {}
+impl !UnknownDataTypeMarker for Text {}
+impl !UnknownDataTypeMarker for DataTypeValue {}
 
 impl !UnknownDataTypeMarker for PhantomData<bool> {}
 impl !UnknownDataTypeMarker for PhantomData<u8> {}
@@ -487,6 +868,90 @@ impl DataDeductor for String {
     fn data_category(&self) -> DataCategory { DataCategory::Categorical }
 }
 
+impl DataDeductor for DataTypeValue {
+    fn data_type(&self) -> DataType {
+        match self {
+            DataTypeValue::Bool(_) => DataType::Bool,
+            DataTypeValue::U8(_) => DataType::U8,
+            DataTypeValue::U16(_) => DataType::U16,
+            DataTypeValue::U32(_) => DataType::U32,
+            DataTypeValue::U64(_) => DataType::U64,
+            DataTypeValue::U128(_) => DataType::U128,
+            DataTypeValue::USize(_) => DataType::USize,
+            DataTypeValue::I8(_) => DataType::I8,
+            DataTypeValue::I16(_) => DataType::I16,
+            DataTypeValue::I32(_) => DataType::I32,
+            DataTypeValue::I64(_) => DataType::I64,
+            DataTypeValue::I128(_) => DataType::I128,
+            DataTypeValue::ISize(_) => DataType::ISize,
+            DataTypeValue::F32(_) => DataType::F32,
+            DataTypeValue::F64(_) => DataType::F64,
+            DataTypeValue::RcStr(_) => DataType::RcStr,
+            DataTypeValue::String(_) => DataType::String,
+            DataTypeValue::Date(v) => v.data_type(),
+            DataTypeValue::Datetime(v) => v.data_type(),
+            DataTypeValue::Duration(v) => v.data_type(),
+            DataTypeValue::Ordinal(v) => v.data_type(),
+            DataTypeValue::Unknown => DataType::Unknown
+        }
+    }
+
+    fn data_category(&self) -> DataCategory {
+        match self {
+            DataTypeValue::Bool(_) | DataTypeValue::RcStr(_) | DataTypeValue::String(_) 
+                | DataTypeValue::Unknown => DataCategory::Categorical,
+            DataTypeValue::Ordinal(_) => DataCategory::Ordinal,
+            _ => DataCategory::Numerical
+        }
+    }
+}
+
+impl DataDeductor for Date {
+    fn data_type(&self) -> DataType { DataType::Date }
+    fn data_category(&self) -> DataCategory { DataCategory::Numerical }
+}
+
+impl DataDeductor for Datetime {
+    fn data_type(&self) -> DataType { DataType::Datetime(self.unit) }
+    fn data_category(&self) -> DataCategory { DataCategory::Numerical }
+}
+
+impl DataDeductor for Duration {
+    fn data_type(&self) -> DataType { DataType::Duration(self.unit) }
+    fn data_category(&self) -> DataCategory { DataCategory::Numerical }
+}
+
+impl DataDeductor for Ordinal {
+    fn data_type(&self) -> DataType { DataType::Ordinal }
+    fn data_category(&self) -> DataCategory { DataCategory::Ordinal }
+}
+
+// no DataType variants, these never reach a DataTypeValue, a DataVec or the codec
+impl DataDeductor for GeoPoint {
+    fn data_type(&self) -> DataType { DataType::Unknown }
+    fn data_category(&self) -> DataCategory { DataCategory::Numerical }
+}
+
+impl DataDeductor for Embedding {
+    fn data_type(&self) -> DataType { DataType::Unknown }
+    fn data_category(&self) -> DataCategory { DataCategory::Numerical }
+}
+
+impl DataDeductor for TaxonomyNode {
+    fn data_type(&self) -> DataType { DataType::Unknown }
+    fn data_category(&self) -> DataCategory { DataCategory::Categorical }
+}
+
+impl DataDeductor for Interval {
+    fn data_type(&self) -> DataType { DataType::Unknown }
+    fn data_category(&self) -> DataCategory { DataCategory::Numerical }
+}
+
+impl DataDeductor for Text {
+    fn data_type(&self) -> DataType { DataType::Unknown }
+    fn data_category(&self) -> DataCategory { DataCategory::Categorical }
+}
+
 impl DataDeductor for PhantomData<bool> {
     fn data_type(&self) -> DataType { DataType::Bool }
     fn data_category(&self) -> DataCategory { DataCategory::Categorical }
@@ -570,4 +1035,143 @@ impl DataDeductor for PhantomData<Rc<str>> {
 impl DataDeductor for PhantomData<String> {
     fn data_type(&self) -> DataType { DataType::String }
     fn data_category(&self) -> DataCategory { DataCategory::Categorical }
-}
\ No newline at end of file
+}
+
+impl DataDeductor for PhantomData<Date> {
+    fn data_type(&self) -> DataType { DataType::Date }
+    fn data_category(&self) -> DataCategory { DataCategory::Numerical }
+}
+
+impl DataDeductor for PhantomData<Datetime> {
+    fn data_type(&self) -> DataType { DataType::Datetime(TimeUnit::default()) }
+    fn data_category(&self) -> DataCategory { DataCategory::Numerical }
+}
+
+impl DataDeductor for PhantomData<Duration> {
+    fn data_type(&self) -> DataType { DataType::Duration(TimeUnit::default()) }
+    fn data_category(&self) -> DataCategory { DataCategory::Numerical }
+}
+
+impl DataDeductor for PhantomData<Ordinal> {
+    fn data_type(&self) -> DataType { DataType::Ordinal }
+    fn data_category(&self) -> DataCategory { DataCategory::Ordinal }
+}
+
+impl DataDeductor for PhantomData<GeoPoint> {
+    fn data_type(&self) -> DataType { DataType::Unknown }
+    fn data_category(&self) -> DataCategory { DataCategory::Numerical }
+}
+
+impl DataDeductor for PhantomData<Embedding> {
+    fn data_type(&self) -> DataType { DataType::Unknown }
+    fn data_category(&self) -> DataCategory { DataCategory::Numerical }
+}
+
+impl DataDeductor for PhantomData<TaxonomyNode> {
+    fn data_type(&self) -> DataType { DataType::Unknown }
+    fn data_category(&self) -> DataCategory { DataCategory::Categorical }
+}
+
+impl DataDeductor for PhantomData<Interval> {
+    fn data_type(&self) -> DataType { DataType::Unknown }
+    fn data_category(&self) -> DataCategory { DataCategory::Numerical }
+}
+
+impl DataDeductor for PhantomData<Text> {
+    fn data_type(&self) -> DataType { DataType::Unknown }
+    fn data_category(&self) -> DataCategory { DataCategory::Categorical }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use pretty_assertions::assert_eq;
+
+    use polars::prelude::DataType as PolarsDataType;
+
+    use super::{ DataCategory, DataType, DataTypeValue };
+    use crate::temporal::TimeUnit;
+
+    #[test]
+    fn data_type_introspection() {
+        assert!(DataType::U8.is_numeric() && DataType::U8.is_integer() && !DataType::U8.is_signed());
+        assert!(DataType::F32.is_numeric() && !DataType::F32.is_integer() && DataType::F32.is_signed());
+        assert!(!DataType::String.is_numeric() && !DataType::Date.is_numeric());
+        assert_eq!(DataType::I16.bit_width(), Some(16));
+        assert_eq!(DataType::Bool.bit_width(), Some(1));
+        assert_eq!(DataType::RcStr.bit_width(), None);
+        assert_eq!(DataType::I8.min_value(), Some(DataTypeValue::I8(i8::MIN)));
+        assert_eq!(DataType::U128.max_value(), Some(DataTypeValue::U128(u128::MAX)));
+        assert_eq!(DataType::String.max_value(), None);
+        assert_eq!(DataType::Bool.data_category(), DataCategory::Categorical);
+        assert_eq!(DataType::Datetime(TimeUnit::Nanoseconds).data_category(), DataCategory::Numerical);
+        assert_eq!(DataType::Ordinal.data_category(), DataCategory::Ordinal);
+
+        let schema: HashSet<DataType> = [DataType::U8, DataType::U8, DataType::F64].into();
+        assert_eq!(schema.len(), 2);
+    }
+
+    #[test]
+    fn data_type_polars_mapping() {
+        for data_type in [
+            DataType::Bool, DataType::U16, DataType::I64, DataType::F32, DataType::String,
+            DataType::Date, DataType::Datetime(TimeUnit::Microseconds),
+            DataType::Duration(TimeUnit::Nanoseconds)
+        ] {
+            let polars_type = data_type.to_polars().unwrap();
+            assert_eq!(DataType::from(&polars_type), data_type);
+        }
+        assert_eq!(DataType::RcStr.to_polars(), Some(PolarsDataType::Utf8));
+        assert_eq!(DataType::I128.to_polars(), None);
+        assert_eq!(DataType::from(&PolarsDataType::Null), DataType::Unknown);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        use std::rc::Rc;
+
+        use crate::temporal::Datetime;
+
+        let timezone = Some(Rc::from("+02:00"));
+        let values = vec![
+            DataTypeValue::U8(7),
+            DataTypeValue::F64(0.5),
+            DataTypeValue::RcStr(Rc::from("a")),
+            DataTypeValue::Datetime(Datetime::new(1, TimeUnit::Milliseconds, timezone))
+        ];
+        let json = serde_json::to_string(&values).unwrap();
+        assert_eq!(&json[..30], r#"[{"U8":7},{"F64":0.5},{"RcStr""#);
+        assert!(json.contains(r#"{"RcStr":"a"}"#));
+        let decoded: Vec<DataTypeValue> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, values);
+
+        let schema = vec![DataType::U8, DataType::Duration(TimeUnit::Nanoseconds)];
+        let json = serde_json::to_string(&schema).unwrap();
+        assert_eq!(json, r#"["U8",{"Duration":"Nanoseconds"}]"#);
+        assert_eq!(serde_json::from_str::<Vec<DataType>>(&json).unwrap(), schema);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_validates_values() {
+        use crate::{ ordinal::{ Ordinal, OrdinalScale }, geo::GeoPoint, embedding::Embedding };
+
+        let level = OrdinalScale::new(&["low", "high"]).unwrap().value("high").unwrap();
+        let json = serde_json::to_string(&level).unwrap();
+        assert_eq!(json, r#"{"scale":{"levels":["low","high"]},"rank":1}"#);
+        assert_eq!(serde_json::from_str::<Ordinal>(&json).unwrap(), level);
+        assert!(serde_json::from_str::<Ordinal>(&json.replace("1}", "2}")).is_err());
+        assert!(serde_json::from_str::<OrdinalScale>(r#"{"levels":[]}"#).is_err());
+        assert!(serde_json::from_str::<OrdinalScale>(r#"{"levels":["a","a"]}"#).is_err());
+
+        let point = serde_json::from_str::<GeoPoint>(r#"{"lat":52.2,"lon":21.0}"#).unwrap();
+        assert_eq!(point, GeoPoint::new(52.2, 21.0).unwrap());
+        assert!(serde_json::from_str::<GeoPoint>(r#"{"lat":91.0,"lon":0.0}"#).is_err());
+
+        let embedding = r#"{"values":[1.0,2.0],"metric":"Cosine"}"#;
+        assert_eq!(serde_json::from_str::<Embedding>(embedding).unwrap().dim(), 2);
+        assert!(serde_json::from_str::<Embedding>(r#"{"values":[],"metric":"Dot"}"#).is_err());
+    }
+}
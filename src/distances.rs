@@ -2,10 +2,29 @@ use std::rc::Rc;
 
 use num_traits::ToPrimitive;
 
+use crate::{
+    data::{ DataTypeValue, DataCategory },
+    numeric::TWO_POW_128,
+    polars::DataVec
+};
+
 pub trait Distance {
     fn distance(&self, v: &Self) -> f64;
 }
 
+// |a - b| of integers without rounding, any two values of one integer type are at most
+// u128::MAX apart
+pub trait ExactDistance {
+    fn exact_distance(&self, v: &Self) -> u128;
+
+    // nearest f64 and whether it equals the exact distance
+    fn checked_distance(&self, v: &Self) -> (f64, bool) {
+        let distance = self.exact_distance(v);
+        let rounded = distance as f64;
+        (rounded, rounded < TWO_POW_128 && rounded as u128 == distance)
+    }
+}
+
 impl Distance for str {
     fn distance(&self, v: &str) -> f64  {
         if *self == *v { 0.0 } else { 1.0 }
@@ -24,6 +43,19 @@ impl Distance for Rc<str> {
     }
 }
 
+macro_rules! impl_integer_distance {
+    ( $($t:ty),* ) => {
+        $( impl ExactDistance for $t {
+            fn exact_distance(&self, v: &Self) -> u128 { self.abs_diff(*v) as u128 }
+        }
+
+        // rounded once after the exact subtraction
+        impl Distance for $t {
+            fn distance(&self, v: &Self) -> f64 { self.exact_distance(v) as f64 }
+        }) *
+    }
+}
+
 macro_rules! impl_distance {
     ( $($t:ty),* ) => {
         $( impl Distance for $t {
@@ -36,17 +68,199 @@ macro_rules! impl_distance {
     }
 }
 
-impl_distance! { 
+impl_integer_distance! {
     i8, i16, i32, i64, i128, isize,
-    u8, u16, u32, u64, u128, usize,
-    f32, f64
+    u8, u16, u32, u64, u128, usize
+}
+
+impl_distance! { f32, f64 }
+
+// how a column missing on either side, Unknown or a NaN float, enters the Gower distance
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MissingValues {
+    // left out of both the sum and the total weight
+    #[default]
+    Skip,
+    // counted as maximally different
+    Maximal,
+    // makes the whole distance NaN
+    Propagate
+}
+
+fn is_missing(value: &DataTypeValue) -> bool {
+    match value {
+        DataTypeValue::Unknown => true,
+        DataTypeValue::F32(v) => v.is_nan(),
+        DataTypeValue::F64(v) => v.is_nan(),
+        _ => false
+    }
+}
+
+// one column of a Gower distance, the range is only used by numerical columns
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GowerColumn {
+    category: DataCategory,
+    range: f64,
+    weight: f64
+}
+
+impl GowerColumn {
+    // range in the units of DataTypeValue::distance, None unless finite and non-negative
+    pub fn numerical(range: f64) -> Option<GowerColumn> {
+        if range >= 0.0 && range.is_finite() {
+            Some(GowerColumn { category: DataCategory::Numerical, range, weight: 1.0 })
+        } else {
+            None
+        }
+    }
+
+    // 0 for equal values and 1 otherwise
+    pub fn categorical() -> GowerColumn {
+        GowerColumn { category: DataCategory::Categorical, range: 0.0, weight: 1.0 }
+    }
+
+    // rank difference normalized over the whole scale
+    pub fn ordinal() -> GowerColumn {
+        GowerColumn { category: DataCategory::Ordinal, range: 0.0, weight: 1.0 }
+    }
+
+    // range between the smallest and largest valid values, 0 without any
+    pub fn from_values<'a, I>(category: DataCategory, values: I) -> GowerColumn
+    where I: IntoIterator<Item = &'a DataTypeValue> {
+        let mut bounds: Option<(&DataTypeValue, &DataTypeValue)> = None;
+        if category == DataCategory::Numerical {
+            for value in values.into_iter().filter(|x| !is_missing(x)) {
+                bounds = match bounds {
+                    None => Some((value, value)),
+                    Some((min, max)) => Some((
+                        if value < min { value } else { min },
+                        if value > max { value } else { max }
+                    ))
+                };
+            }
+        }
+        let range = bounds.map(|(min, max)| min.distance(max)).filter(|x| x.is_finite());
+        GowerColumn { category, range: range.unwrap_or(0.0), weight: 1.0 }
+    }
+
+    // category of the column type
+    pub fn from_datavec(column: &DataVec) -> GowerColumn {
+        let values: Vec<DataTypeValue> = column.iter_valid().collect();
+        GowerColumn::from_values(column.data_type().data_category(), &values)
+    }
+
+    // None unless finite and non-negative
+    pub fn with_weight(self, weight: f64) -> Option<GowerColumn> {
+        if weight >= 0.0 && weight.is_finite() {
+            Some(GowerColumn { weight, ..self })
+        } else {
+            None
+        }
+    }
+
+    pub fn category(&self) -> DataCategory { self.category }
+
+    pub fn range(&self) -> f64 { self.range }
+
+    pub fn weight(&self) -> f64 { self.weight }
+
+    // in [0, 1], a zero range gives 0 for equal values and 1 otherwise,
+    // NaN for missing or incompatible values
+    pub fn distance(&self, lhs: &DataTypeValue, rhs: &DataTypeValue) -> f64 {
+        if is_missing(lhs) || is_missing(rhs) { return f64::NAN }
+        match self.category {
+            DataCategory::Numerical => {
+                let distance = lhs.distance(rhs);
+                if distance.is_nan() || distance == 0.0 {
+                    distance
+                } else if self.range == 0.0 {
+                    1.0
+                } else {
+                    (distance / self.range).min(1.0)
+                }
+            }
+            DataCategory::Categorical => if lhs == rhs { 0.0 } else { 1.0 },
+            DataCategory::Ordinal => lhs.distance(rhs)
+        }
+    }
+}
+
+// record-level distance over mixed columns, the weighted mean of the column distances
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gower {
+    columns: Vec<GowerColumn>,
+    missing: MissingValues
+}
+
+impl Gower {
+    pub fn new(columns: Vec<GowerColumn>) -> Gower {
+        Gower { columns, missing: MissingValues::default() }
+    }
+
+    // one column per DataVec, ordinal columns have to be described with GowerColumn
+    pub fn from_datavecs(columns: &[DataVec]) -> Gower {
+        Gower::new(columns.iter().map(GowerColumn::from_datavec).collect())
+    }
+
+    // None for a weight per column mismatch, negative or non-finite weights
+    pub fn with_weights(self, weights: &[f64]) -> Option<Gower> {
+        if weights.len() != self.columns.len() { return None }
+        let columns = self.columns.iter()
+            .zip(weights)
+            .map(|(column, weight)| column.with_weight(*weight))
+            .collect::<Option<Vec<GowerColumn>>>()?;
+        Some(Gower { columns, ..self })
+    }
+
+    pub fn with_missing(self, missing: MissingValues) -> Gower { Gower { missing, ..self } }
+
+    pub fn columns(&self) -> &[GowerColumn] { &self.columns }
+
+    pub fn missing(&self) -> MissingValues { self.missing }
+
+    // NaN for records of another length, incompatible values
+    // or when no weighted column is left to compare
+    pub fn distance(&self, lhs: &[DataTypeValue], rhs: &[DataTypeValue]) -> f64 {
+        if lhs.len() != self.columns.len() || rhs.len() != self.columns.len() { return f64::NAN }
+        let (mut sum, mut total) = (0.0, 0.0);
+        for ((column, lhs), rhs) in self.columns.iter().zip(lhs).zip(rhs) {
+            let distance = if is_missing(lhs) || is_missing(rhs) {
+                match self.missing {
+                    MissingValues::Skip => continue,
+                    MissingValues::Maximal => 1.0,
+                    MissingValues::Propagate => return f64::NAN
+                }
+            } else {
+                column.distance(lhs, rhs)
+            };
+            sum += column.weight * distance;
+            total += column.weight;
+        }
+        if total == 0.0 { f64::NAN } else { sum / total }
+    }
+
+    // distance between rows i and j of the columns, nulls are missing values
+    pub fn distance_between_rows(&self, columns: &[DataVec], i: usize, j: usize) -> f64 {
+        let row = |i: usize| -> Vec<DataTypeValue> {
+            columns.iter().map(|x| x.get(i).unwrap_or(DataTypeValue::Unknown)).collect()
+        };
+        self.distance(&row(i), &row(j))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
 
-    use super::Distance;
+    use super::{ Distance, ExactDistance, Gower, GowerColumn, MissingValues };
+    use crate::{
+        data::{ DataTypeValue, DataCategory },
+        ordinal::OrdinalScale,
+        polars::DataVec
+    };
 
     #[test]
     fn distance_f32() {
@@ -74,4 +288,89 @@ mod tests {
         assert_eq!("a".distance(&"a"), 0.0);
         assert_eq!("a".distance(&"b"), 1.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn exact_integer_distances() {
+        // distinct values 2^60 apart by one collapse to the same f64
+        let id = 1u64 << 60;
+        assert_eq!(id as f64, (id + 1) as f64);
+        assert_eq!(id.distance(&(id + 1)), 1.0);
+        assert_eq!(id.exact_distance(&(id + 1)), 1);
+
+        assert_eq!(u64::MAX.exact_distance(&0), u64::MAX as u128);
+        assert_eq!(i64::MIN.exact_distance(&i64::MAX), u64::MAX as u128);
+        assert_eq!(i8::MIN.exact_distance(&i8::MAX), 255);
+        assert_eq!(isize::MIN.exact_distance(&isize::MAX), usize::MAX as u128);
+        assert_eq!(u128::MAX.exact_distance(&0), u128::MAX);
+        assert_eq!(i128::MIN.exact_distance(&i128::MAX), u128::MAX);
+        assert_eq!(i128::MAX.exact_distance(&i128::MIN), u128::MAX);
+        assert_eq!(i128::MIN.exact_distance(&i128::MIN), 0);
+
+        assert_eq!(u64::MAX.checked_distance(&0), (u64::MAX as f64, false));
+        assert_eq!(u32::MAX.checked_distance(&0), (u32::MAX as f64, true));
+        assert_eq!((1u128 << 100).checked_distance(&0), (2f64.powi(100), true));
+        assert_eq!(u128::MAX.checked_distance(&0), (2f64.powi(128), false));
+        assert_eq!(i128::MIN.checked_distance(&0), (2f64.powi(127), true));
+    }
+
+    #[test]
+    fn gower_mixed_records() {
+        let ages = DataVec::from(vec![Some(20i64), Some(60), None, Some(40)]);
+        let colors = DataVec::from(vec![
+            Some("red".to_string()), Some("blue".to_string()), Some("red".to_string()), None
+        ]);
+        let gower = Gower::from_datavecs(&[ages.clone(), colors.clone()]);
+        assert_eq!(gower.columns()[0].range(), 40.0);
+        assert_eq!(gower.columns()[1].category(), DataCategory::Categorical);
+        assert_eq!(gower.distance_between_rows(&[ages.clone(), colors.clone()], 0, 1), 1.0);
+        assert_eq!(gower.distance_between_rows(&[ages.clone(), colors.clone()], 0, 3), 0.5);
+        // the missing age is skipped, only the equal colors remain
+        assert_eq!(gower.distance_between_rows(&[ages.clone(), colors.clone()], 0, 2), 0.0);
+        let maximal = gower.clone().with_missing(MissingValues::Maximal);
+        assert_eq!(maximal.distance_between_rows(&[ages.clone(), colors.clone()], 0, 2), 0.5);
+        let propagate = gower.clone().with_missing(MissingValues::Propagate);
+        assert!(propagate.distance_between_rows(&[ages, colors], 0, 2).is_nan());
+
+        let scale = OrdinalScale::new(&["low", "mid", "high"]).unwrap();
+        let level = |x: &str| DataTypeValue::Ordinal(scale.value(x).unwrap());
+        let gower = Gower::new(vec![
+            GowerColumn::numerical(10.0).unwrap(),
+            GowerColumn::categorical(),
+            GowerColumn::ordinal()
+        ]);
+        let lhs = [DataTypeValue::U8(1), DataTypeValue::Bool(true), level("low")];
+        let rhs = [DataTypeValue::I32(6), DataTypeValue::Bool(true), level("high")];
+        assert_eq!(gower.distance(&lhs, &rhs), 0.5);
+        let weighted = gower.clone().with_weights(&[2.0, 1.0, 1.0]).unwrap();
+        assert_eq!(weighted.distance(&lhs, &rhs), 0.5);
+        let weighted = gower.clone().with_weights(&[0.0, 1.0, 3.0]).unwrap();
+        assert_eq!(weighted.distance(&lhs, &rhs), 0.75);
+        assert!(gower.clone().with_weights(&[1.0]).is_none());
+        assert!(gower.clone().with_weights(&[1.0, -1.0, 1.0]).is_none());
+        assert!(gower.distance(&lhs, &rhs[..2]).is_nan());
+        assert!(gower.distance(&lhs, &[
+            DataTypeValue::String("x".to_string()), DataTypeValue::Bool(true), level("low")
+        ]).is_nan());
+    }
+
+    #[test]
+    fn gower_column_ranges() {
+        let wide = [DataTypeValue::U64(1 << 60), DataTypeValue::F64(f64::NAN),
+            DataTypeValue::U64((1 << 60) + 4)];
+        let column = GowerColumn::from_values(DataCategory::Numerical, &wide);
+        assert_eq!(column.range(), 4.0);
+        assert_eq!(column.distance(&wide[0], &DataTypeValue::U64((1 << 60) + 1)), 0.25);
+        assert_eq!(column.distance(&wide[0], &DataTypeValue::I8(0)), 1.0);
+        assert!(column.distance(&wide[0], &DataTypeValue::Unknown).is_nan());
+
+        let constant = GowerColumn::from_values(DataCategory::Numerical, &[DataTypeValue::I8(3)]);
+        assert_eq!(constant.distance(&DataTypeValue::I8(3), &DataTypeValue::U8(3)), 0.0);
+        assert_eq!(constant.distance(&DataTypeValue::I8(3), &DataTypeValue::U8(4)), 1.0);
+        assert_eq!(GowerColumn::from_values(DataCategory::Numerical, &[]).range(), 0.0);
+        assert!(GowerColumn::numerical(f64::INFINITY).is_none());
+        assert!(GowerColumn::categorical().with_weight(f64::NAN).is_none());
+
+        let missing = [DataTypeValue::Unknown];
+        assert!(Gower::new(vec![GowerColumn::categorical()]).distance(&missing, &missing).is_nan());
+    }
+}
@@ -0,0 +1,239 @@
+use std::{
+    rc::Rc,
+    cmp::Ordering,
+    fmt::{ Display, Formatter, Result as FmtResult }
+};
+
+use crate::distances::Distance;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VectorMetric {
+    // 1 - cosine similarity, in [0, 2]
+    Cosine,
+    #[default]
+    Euclidean,
+    // e^-dot, in (0, inf], the larger the product the closer the vectors
+    Dot
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VectorError {
+    Empty,
+    NotFinite(usize),
+    DimensionMismatch { expected: usize, found: usize },
+    MetricMismatch(VectorMetric, VectorMetric),
+    ZeroNorm
+}
+
+impl Display for VectorError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            VectorError::Empty => write!(f, "vector has no dimensions"),
+            VectorError::NotFinite(i) => write!(f, "element {} is not finite", i),
+            VectorError::DimensionMismatch { expected, found } => {
+                write!(f, "expected {} dimensions, found {}", expected, found)
+            }
+            VectorError::MetricMismatch(lhs, rhs) => {
+                write!(f, "vectors compared with {:?} and {:?}", lhs, rhs)
+            }
+            VectorError::ZeroNorm => write!(f, "cosine distance of a zero vector")
+        }
+    }
+}
+
+impl std::error::Error for VectorError {}
+
+// immutable f32 vector shared between clones, compared in the metric both sides share
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "EmbeddingFields"))]
+pub struct Embedding {
+    values: Rc<[f32]>,
+    metric: VectorMetric
+}
+
+// deserialized vectors go through Embedding::new
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct EmbeddingFields {
+    values: Vec<f32>,
+    metric: VectorMetric
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<EmbeddingFields> for Embedding {
+    type Error = VectorError;
+
+    fn try_from(fields: EmbeddingFields) -> Result<Embedding, VectorError> {
+        Embedding::new(fields.values, fields.metric)
+    }
+}
+
+impl Embedding {
+    pub fn new(values: Vec<f32>, metric: VectorMetric) -> Result<Embedding, VectorError> {
+        if values.is_empty() { return Err(VectorError::Empty) }
+        if let Some(i) = values.iter().position(|x| !x.is_finite()) {
+            return Err(VectorError::NotFinite(i))
+        }
+        Ok(Embedding { values: Rc::from(values), metric })
+    }
+
+    pub fn values(&self) -> &[f32] { &self.values }
+
+    pub fn dim(&self) -> usize { self.values.len() }
+
+    pub fn metric(&self) -> VectorMetric { self.metric }
+
+    pub fn with_metric(&self, metric: VectorMetric) -> Embedding {
+        Embedding { values: self.values.clone(), metric }
+    }
+
+    pub fn norm(&self) -> f64 { self.dot(self).unwrap_or(0.0).sqrt() }
+
+    fn check_dim(&self, other: &Embedding) -> Result<(), VectorError> {
+        if self.dim() == other.dim() {
+            Ok(())
+        } else {
+            Err(VectorError::DimensionMismatch { expected: self.dim(), found: other.dim() })
+        }
+    }
+
+    pub fn dot(&self, other: &Embedding) -> Result<f64, VectorError> {
+        self.check_dim(other)?;
+        Ok(self.values.iter().zip(other.values.iter()).map(|(a, b)| *a as f64 * *b as f64).sum())
+    }
+
+    pub fn cosine_similarity(&self, other: &Embedding) -> Result<f64, VectorError> {
+        let dot = self.dot(other)?;
+        let norms = self.norm() * other.norm();
+        if norms == 0.0 { return Err(VectorError::ZeroNorm) }
+        Ok((dot / norms).clamp(-1.0, 1.0))
+    }
+
+    pub fn euclidean(&self, other: &Embedding) -> Result<f64, VectorError> {
+        self.check_dim(other)?;
+        Ok(
+            self.values.iter().zip(other.values.iter())
+                .map(|(a, b)| (*a as f64 - *b as f64).powi(2))
+                .sum::<f64>()
+                .sqrt()
+        )
+    }
+
+    pub fn distance_with(
+        &self, other: &Embedding, metric: VectorMetric
+    ) -> Result<f64, VectorError> {
+        match metric {
+            VectorMetric::Cosine => Ok(1.0 - self.cosine_similarity(other)?),
+            VectorMetric::Euclidean => self.euclidean(other),
+            VectorMetric::Dot => Ok((-self.dot(other)?).exp())
+        }
+    }
+
+    // the metrics of both vectors have to agree, so that the distance is symmetric
+    pub fn try_distance(&self, other: &Embedding) -> Result<f64, VectorError> {
+        if self.metric != other.metric {
+            return Err(VectorError::MetricMismatch(self.metric, other.metric))
+        }
+        self.distance_with(other, self.metric)
+    }
+}
+
+impl PartialEq for Embedding {
+    fn eq(&self, other: &Embedding) -> bool { self.values == other.values }
+}
+
+// lexicographic by elements
+impl PartialOrd for Embedding {
+    fn partial_cmp(&self, other: &Embedding) -> Option<Ordering> {
+        self.values.partial_cmp(&other.values)
+    }
+}
+
+impl Display for Embedding {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{:?}", self.values)
+    }
+}
+
+// distance in the shared metric, NaN for mismatched dimensions or metrics
+impl Distance for Embedding {
+    fn distance(&self, v: &Embedding) -> f64 { self.try_distance(v).unwrap_or(f64::NAN) }
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::{ Series, NamedFrom };
+
+    use pretty_assertions::assert_eq;
+
+    use super::{ Embedding, VectorMetric, VectorError };
+    use crate::{
+        distances::Distance,
+        sensor::SensorData,
+        similarity::Similarity,
+        polars::series_to_embeddings
+    };
+
+    #[test]
+    fn metrics() {
+        let x = Embedding::new(vec![1.0, 0.0], VectorMetric::Cosine).unwrap();
+        let y = Embedding::new(vec![0.0, 2.0], VectorMetric::Euclidean).unwrap();
+        assert_eq!(SensorData::distance(&x, &y.with_metric(VectorMetric::Cosine)), 1.0);
+        assert_eq!(SensorData::distance(&x.with_metric(VectorMetric::Euclidean), &y), 5f64.sqrt());
+        assert!(SensorData::distance(&x, &y).is_nan() && SensorData::distance(&y, &x).is_nan());
+        assert_eq!(
+            x.try_distance(&y),
+            Err(VectorError::MetricMismatch(VectorMetric::Cosine, VectorMetric::Euclidean))
+        );
+        let dot = x.with_metric(VectorMetric::Dot);
+        assert_eq!(x.distance_with(&dot, VectorMetric::Dot), Ok((-1f64).exp()));
+        assert_eq!(x.distance_with(&x, VectorMetric::Cosine), Ok(0.0));
+
+        let z = Embedding::new(vec![1.0, 2.0, 3.0], VectorMetric::Cosine).unwrap();
+        assert_eq!(
+            x.try_distance(&z), Err(VectorError::DimensionMismatch { expected: 2, found: 3 })
+        );
+        assert!(SensorData::distance(&x, &z).is_nan());
+
+        let zero = Embedding::new(vec![0.0, 0.0], VectorMetric::Cosine).unwrap();
+        assert_eq!(zero.try_distance(&x), Err(VectorError::ZeroNorm));
+        assert_eq!(Embedding::new(vec![], VectorMetric::Dot).unwrap_err(), VectorError::Empty);
+        assert_eq!(
+            Embedding::new(vec![1.0, f32::NAN], VectorMetric::Dot).unwrap_err(),
+            VectorError::NotFinite(1)
+        );
+    }
+
+    #[test]
+    fn dot_distances_activate() {
+        let x = Embedding::new(vec![1.0, 1.0], VectorMetric::Dot).unwrap();
+        let near = Embedding::new(vec![2.0, 2.0], VectorMetric::Dot).unwrap();
+        let far = Embedding::new(vec![-1.0, -1.0], VectorMetric::Dot).unwrap();
+        let (near, far) = (Distance::distance(&x, &near), Distance::distance(&x, &far));
+        assert!(near >= 0.0 && near < far);
+
+        let similarity = Similarity::default();
+        let (near, far) = (similarity.similarity(near), similarity.similarity(far));
+        assert!(near > far && near > 0.0);
+    }
+
+    #[test]
+    fn embeddings_from_list_series() {
+        let series = Series::new("embedding", &[
+            Series::new("", &[1.0f32, 0.0]),
+            Series::new("", &[0.5f32, 0.5])
+        ]);
+        let embeddings = series_to_embeddings(&series, VectorMetric::Cosine).unwrap();
+        assert_eq!(embeddings.len(), 2);
+        assert_eq!(embeddings[1].as_ref().unwrap().values(), &[0.5, 0.5]);
+
+        let ragged = Series::new("embedding", &[
+            Series::new("", &[1.0f32, 0.0]),
+            Series::new("", &[0.5f32])
+        ]);
+        assert!(series_to_embeddings(&ragged, VectorMetric::Cosine).is_err());
+        assert!(series_to_embeddings(&Series::new("x", &[1.0f32]), VectorMetric::Cosine).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use std::{
+    cmp::Ordering,
+    fmt::{ Display, Formatter, Result as FmtResult }
+};
+
+use crate::distances::Distance;
+
+pub const EARTH_RADIUS: f64 = 6_371_008.8;
+
+// WGS-84 ellipsoid
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+const WGS84_B: f64 = WGS84_A * (1.0 - WGS84_F);
+
+// latitude and longitude in degrees
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "GeoPointFields"))]
+pub struct GeoPoint {
+    lat: f64,
+    lon: f64
+}
+
+// deserialized points go through GeoPoint::new
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct GeoPointFields {
+    lat: f64,
+    lon: f64
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<GeoPointFields> for GeoPoint {
+    type Error = String;
+
+    fn try_from(fields: GeoPointFields) -> Result<GeoPoint, String> {
+        GeoPoint::new(fields.lat, fields.lon)
+            .ok_or_else(|| format!("coordinates {}, {} out of range", fields.lat, fields.lon))
+    }
+}
+
+impl GeoPoint {
+    pub fn new(lat: f64, lon: f64) -> Option<GeoPoint> {
+        if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) {
+            Some(GeoPoint { lat, lon })
+        } else {
+            None
+        }
+    }
+
+    pub fn lat(&self) -> f64 { self.lat }
+
+    pub fn lon(&self) -> f64 { self.lon }
+
+    // great-circle distance on a sphere of the mean earth radius in metres
+    pub fn haversine(&self, other: &GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let dlat = lat2 - lat1;
+        let dlon = (other.lon - self.lon).to_radians();
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS * a.sqrt().min(1.0).asin()
+    }
+
+    // geodesic distance on the WGS-84 ellipsoid in metres,
+    // None if the iteration does not converge (nearly antipodal points)
+    pub fn vincenty(&self, other: &GeoPoint) -> Option<f64> {
+        let l = (other.lon - self.lon).to_radians();
+        let u1 = ((1.0 - WGS84_F) * self.lat.to_radians().tan()).atan();
+        let u2 = ((1.0 - WGS84_F) * other.lat.to_radians().tan()).atan();
+        let (sin_u1, cos_u1) = u1.sin_cos();
+        let (sin_u2, cos_u2) = u2.sin_cos();
+
+        let mut lambda = l;
+        for _ in 0..200 {
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+            let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+                + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2)).sqrt();
+            if sin_sigma == 0.0 { return Some(0.0) }
+            let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+            let sigma = sin_sigma.atan2(cos_sigma);
+            let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+            let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+            let cos_2sigma_m = if cos2_alpha == 0.0 {
+                0.0
+            } else {
+                cos_sigma - 2.0 * sin_u1 * sin_u2 / cos2_alpha
+            };
+            let c = WGS84_F / 16.0 * cos2_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos2_alpha));
+            let previous = lambda;
+            lambda = l + (1.0 - c) * WGS84_F * sin_alpha * (sigma + c * sin_sigma
+                * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+
+            if (lambda - previous).abs() < 1e-12 {
+                let u2 = cos2_alpha * (WGS84_A * WGS84_A - WGS84_B * WGS84_B) / (WGS84_B * WGS84_B);
+                let a = 1.0 + u2 / 16384.0 * (4096.0 + u2 * (-768.0 + u2 * (320.0 - 175.0 * u2)));
+                let b = u2 / 1024.0 * (256.0 + u2 * (-128.0 + u2 * (74.0 - 47.0 * u2)));
+                let delta_sigma = b * sin_sigma * (cos_2sigma_m + b / 4.0
+                    * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)
+                        - b / 6.0 * cos_2sigma_m * (-3.0 + 4.0 * sin_sigma * sin_sigma)
+                            * (-3.0 + 4.0 * cos_2sigma_m * cos_2sigma_m)));
+                return Some(WGS84_B * a * (sigma - delta_sigma))
+            }
+        }
+        None
+    }
+}
+
+// by latitude, then longitude
+impl PartialOrd for GeoPoint {
+    fn partial_cmp(&self, other: &GeoPoint) -> Option<Ordering> {
+        match self.lat.partial_cmp(&other.lat)? {
+            Ordering::Equal => self.lon.partial_cmp(&other.lon),
+            ordering => Some(ordering)
+        }
+    }
+}
+
+impl Display for GeoPoint {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "({}, {})", self.lat, self.lon)
+    }
+}
+
+// haversine distance in metres
+impl Distance for GeoPoint {
+    fn distance(&self, v: &GeoPoint) -> f64 { self.haversine(v) }
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::{ PolarsError, Series, NamedFrom };
+
+    use super::GeoPoint;
+    use crate::{
+        distances::Distance,
+        sensor::SensorData,
+        polars::series_to_geopoints
+    };
+
+    #[test]
+    fn geo_distances() {
+        let warsaw = GeoPoint::new(52.2297, 21.0122).unwrap();
+        let krakow = GeoPoint::new(50.0647, 19.9450).unwrap();
+        let haversine = Distance::distance(&warsaw, &krakow);
+        assert!((haversine - 252_000.0).abs() < 1_000.0, "{}", haversine);
+        assert_eq!(SensorData::distance(&warsaw, &krakow), haversine);
+
+        let vincenty = warsaw.vincenty(&krakow).unwrap();
+        assert!((vincenty - haversine).abs() < 0.005 * haversine, "{}", vincenty);
+        assert_eq!(warsaw.vincenty(&warsaw), Some(0.0));
+
+        // one degree of longitude on the equator
+        let equator = GeoPoint::new(0.0, 0.0).unwrap().vincenty(&GeoPoint::new(0.0, 1.0).unwrap());
+        assert!((equator.unwrap() - 111_319.49).abs() < 0.01);
+
+        assert!(GeoPoint::new(91.0, 0.0).is_none());
+        assert!(GeoPoint::new(0.0, f64::NAN).is_none());
+        assert!(warsaw > krakow);
+    }
+
+    #[test]
+    fn points_from_columns() {
+        let lat = Series::new("lat", &[Some(52.2297f32), None, Some(95.0)]);
+        let lon = Series::new("lon", &[21.0122, 0.0, 0.0]);
+        let points = series_to_geopoints(&lat, &lon).unwrap();
+        assert_eq!(points.len(), 3);
+        assert!((points[0].unwrap().lat() - 52.2297).abs() < 1e-5);
+        assert_eq!(points[1], None);
+        assert_eq!(points[2], None);
+
+        let short = Series::new("lon", &[21.0122]);
+        assert!(matches!(
+            series_to_geopoints(&lat, &short), Err(PolarsError::ShapeMisMatch(_))
+        ));
+    }
+}
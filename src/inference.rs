@@ -0,0 +1,171 @@
+use std::collections::HashSet;
+
+use crate::data::{ DataCategory, DataType, DataTypeValue, DataTypeValueStr };
+
+#[derive(Debug, Clone)]
+pub struct InferenceOptions {
+    pub sample_size: usize,
+    pub min_confidence: f64,
+    pub categorical_max_distinct: usize
+}
+
+impl Default for InferenceOptions {
+    fn default() -> InferenceOptions {
+        InferenceOptions { sample_size: 1000, min_confidence: 1.0, categorical_max_distinct: 10 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseFailure {
+    pub row: usize,
+    pub cell: String
+}
+
+#[derive(Debug)]
+pub struct InferredColumn {
+    pub data_type: DataType,
+    pub data_category: DataCategory,
+    pub confidence: f64,
+    pub failures: Vec<ParseFailure>,
+    pub values: Vec<Option<DataTypeValue>>
+}
+
+// from the narrowest to the widest, the last one always fits
+const CANDIDATES: [DataType; 14] = [
+    DataType::Bool,
+    DataType::U8, DataType::I8,
+    DataType::U16, DataType::I16,
+    DataType::U32, DataType::I32,
+    DataType::U64, DataType::I64,
+    DataType::U128, DataType::I128,
+    DataType::F32, DataType::F64,
+    DataType::String
+];
+
+fn is_empty(cell: &str) -> bool { cell.trim().is_empty() }
+
+fn parse(cell: &str, data_type: DataType) -> Option<DataTypeValue> {
+    let cell = if data_type == DataType::String { cell } else { cell.trim() };
+    let value = DataTypeValueStr(cell).data_type_value(data_type)?;
+    // "nan", "inf" and "infinity" parse as floats but are not measurements
+    match value {
+        DataTypeValue::F32(v) if !v.is_finite() => return None,
+        DataTypeValue::F64(v) if !v.is_finite() => return None,
+        _ => {}
+    }
+    if data_type == DataType::F32 {
+        let exact: f64 = cell.parse().ok()?;
+        if value.as_f32().map(|v| *v as f64) != Some(exact) { return None }
+    }
+    Some(value)
+}
+
+pub fn infer_column<S: AsRef<str>>(cells: &[S], options: &InferenceOptions) -> InferredColumn {
+    let sample: Vec<&str> = cells.iter()
+        .map(|cell| cell.as_ref())
+        .filter(|cell| !is_empty(cell))
+        .take(options.sample_size)
+        .collect();
+
+    if sample.is_empty() {
+        return InferredColumn {
+            data_type: DataType::Unknown,
+            data_category: DataCategory::Categorical,
+            confidence: 0.0,
+            failures: Vec::new(),
+            values: vec![None; cells.len()]
+        }
+    }
+
+    let (data_type, confidence) = CANDIDATES.iter()
+        .map(|data_type| {
+            let parsed = sample.iter().filter(|cell| parse(cell, *data_type).is_some()).count();
+            (*data_type, parsed as f64 / sample.len() as f64)
+        })
+        .find(|(data_type, confidence)| {
+            *confidence >= options.min_confidence || *data_type == DataType::String
+        })
+        .unwrap_or((DataType::String, 1.0));
+
+    let mut failures = Vec::new();
+    let values: Vec<Option<DataTypeValue>> = cells.iter().enumerate().map(|(row, cell)| {
+        let cell = cell.as_ref();
+        if is_empty(cell) { return None }
+        let value = parse(cell, data_type);
+        if value.is_none() { failures.push(ParseFailure { row, cell: cell.to_string() }) }
+        value
+    }).collect();
+
+    let data_category = match data_type {
+        DataType::Bool | DataType::String => DataCategory::Categorical,
+        DataType::F32 | DataType::F64 => DataCategory::Numerical,
+        _ => {
+            let distinct: HashSet<&str> = sample.iter().map(|cell| cell.trim()).collect();
+            if distinct.len() <= options.categorical_max_distinct && distinct.len() < sample.len() {
+                DataCategory::Categorical
+            } else {
+                DataCategory::Numerical
+            }
+        }
+    };
+
+    InferredColumn { data_type, data_category, confidence, failures, values }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{ infer_column, InferenceOptions, ParseFailure };
+    use crate::data::{ DataCategory, DataType, DataTypeValue };
+
+    #[test]
+    fn narrowest_type() {
+        let options = InferenceOptions::default();
+        let column = infer_column(&["true", "", "false"], &options);
+        assert!(matches!(column.data_type, DataType::Bool));
+        assert_eq!(column.values, vec![
+            Some(DataTypeValue::Bool(true)), None, Some(DataTypeValue::Bool(false))
+        ]);
+
+        assert!(matches!(infer_column(&["1", "255"], &options).data_type, DataType::U8));
+        assert!(matches!(infer_column(&["-1", "127"], &options).data_type, DataType::I8));
+        assert!(matches!(infer_column(&["-1", "255"], &options).data_type, DataType::I16));
+        assert!(matches!(infer_column(&["1.5", "2"], &options).data_type, DataType::F32));
+        assert!(matches!(infer_column(&["0.1"], &options).data_type, DataType::F64));
+        assert!(matches!(infer_column(&["1", "a"], &options).data_type, DataType::String));
+        assert!(matches!(infer_column(&["", " "], &options).data_type, DataType::Unknown));
+    }
+
+    #[test]
+    fn non_finite_floats() {
+        let options = InferenceOptions::default();
+        for cell in ["nan", "NaN", "inf", "-infinity", "1e400"] {
+            assert!(matches!(infer_column(&["1.5", cell], &options).data_type, DataType::String));
+        }
+        let options = InferenceOptions { min_confidence: 0.5, ..Default::default() };
+        let column = infer_column(&["1.5", "inf"], &options);
+        assert!(matches!(column.data_type, DataType::F32));
+        assert_eq!(column.failures, vec![ParseFailure { row: 1, cell: "inf".to_string() }]);
+    }
+
+    #[test]
+    fn confidence_and_failures() {
+        let options = InferenceOptions { min_confidence: 0.7, ..Default::default() };
+        let column = infer_column(&["10", "20", "3O", "40"], &options);
+        assert!(matches!(column.data_type, DataType::U8));
+        assert_eq!(column.confidence, 0.75);
+        assert_eq!(column.failures, vec![ParseFailure { row: 2, cell: "3O".to_string() }]);
+        assert_eq!(column.values[2], None);
+    }
+
+    #[test]
+    fn suggested_category() {
+        let options = InferenceOptions::default();
+        let grades = infer_column(&["1", "2", "3", "2", "1", "3"], &options);
+        assert_eq!(grades.data_category, DataCategory::Categorical);
+        let ids: Vec<String> = (0..100).map(|x| x.to_string()).collect();
+        assert_eq!(infer_column(&ids, &options).data_category, DataCategory::Numerical);
+        assert_eq!(infer_column(&["a", "b"], &options).data_category, DataCategory::Categorical);
+    }
+}
@@ -0,0 +1,98 @@
+use std::{
+    rc::Rc,
+    collections::HashSet
+};
+
+// deduplicates strings into shared Rc<str> handles,
+// saved bytes count the string payloads that were not allocated again
+#[derive(Debug, Clone, Default)]
+pub struct StringInterner {
+    strings: HashSet<Rc<str>>,
+    stored_bytes: usize,
+    saved_bytes: usize
+}
+
+impl StringInterner {
+    pub fn new() -> StringInterner { StringInterner::default() }
+
+    pub fn intern(&mut self, s: &str) -> Rc<str> {
+        if let Some(interned) = self.strings.get(s) {
+            self.saved_bytes += s.len();
+            return interned.clone()
+        }
+        let interned: Rc<str> = Rc::from(s);
+        self.stored_bytes += s.len();
+        self.strings.insert(interned.clone());
+        interned
+    }
+
+    pub fn get(&self, s: &str) -> Option<Rc<str>> { self.strings.get(s).cloned() }
+
+    pub fn contains(&self, s: &str) -> bool { self.strings.contains(s) }
+
+    pub fn len(&self) -> usize { self.strings.len() }
+
+    pub fn is_empty(&self) -> bool { self.strings.is_empty() }
+
+    pub fn stored_bytes(&self) -> usize { self.stored_bytes }
+
+    pub fn saved_bytes(&self) -> usize { self.saved_bytes }
+
+    // forgets strings not referenced outside of the interner
+    pub fn shrink(&mut self) {
+        let before = self.strings.len();
+        self.strings.retain(|s| Rc::strong_count(s) > 1);
+        if self.strings.len() != before {
+            self.stored_bytes = self.strings.iter().map(|s| s.len()).sum();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use pretty_assertions::assert_eq;
+
+    use polars::prelude::{ Series, NamedFrom };
+
+    use super::StringInterner;
+    use crate::{
+        neuron::NeuronID,
+        polars::series_to_datavec_interned
+    };
+
+    #[test]
+    fn deduplication() {
+        let mut interner = StringInterner::new();
+        let red = interner.intern("red");
+        let green = interner.intern("green");
+        let red_again = interner.intern("red");
+        assert!(Rc::ptr_eq(&red, &red_again));
+        assert!(!Rc::ptr_eq(&red, &green));
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.stored_bytes(), 8);
+        assert_eq!(interner.saved_bytes(), 3);
+
+        drop(green);
+        interner.shrink();
+        assert_eq!(interner.len(), 1);
+        assert!(interner.contains("red") && !interner.contains("green"));
+        assert_eq!(interner.stored_bytes(), 3);
+    }
+
+    #[test]
+    fn interned_entry_points() {
+        let mut interner = StringInterner::new();
+        let lhs = NeuronID::new_interned("1", "color", &mut interner);
+        let rhs = NeuronID::new_interned("2", "color", &mut interner);
+        assert!(Rc::ptr_eq(&lhs.parent_id, &rhs.parent_id));
+
+        let series = Series::new("color", &[Some("red"), None, Some("red"), Some("blue")]);
+        let column = series_to_datavec_interned(&series, &mut interner).unwrap();
+        let column = column.as_rc_str().unwrap();
+        assert!(Rc::ptr_eq(column.get(0).unwrap(), column.get(2).unwrap()));
+        assert_eq!(column.get(1), None);
+        assert_eq!(interner.saved_bytes(), "color".len() + "red".len());
+    }
+}
@@ -0,0 +1,194 @@
+use std::{
+    cmp::Ordering,
+    fmt::{ Display, Formatter, Result as FmtResult }
+};
+
+use crate::{
+    data::DataTypeValue,
+    numeric::Numeric,
+    distances::Distance
+};
+
+// closed interval [lower, upper] between numeric values, the bounds may be of different variants
+#[derive(Debug, Clone)]
+pub struct Interval {
+    lower: DataTypeValue,
+    upper: DataTypeValue
+}
+
+fn numeric(value: &DataTypeValue) -> Result<Numeric, String> {
+    match value.to_numeric() {
+        Some(number) if !number.is_nan() => Ok(number),
+        _ => Err(format!("{} is not a numeric interval bound", value))
+    }
+}
+
+fn parse_bound(s: &str) -> Option<DataTypeValue> {
+    let s = s.trim();
+    s.parse().map(DataTypeValue::I64).or_else(|_| s.parse().map(DataTypeValue::F64)).ok()
+}
+
+impl Interval {
+    pub fn new(lower: DataTypeValue, upper: DataTypeValue) -> Result<Interval, String> {
+        if numeric(&lower)? > numeric(&upper)? {
+            return Err(format!("interval lower bound {} exceeds upper bound {}", lower, upper))
+        }
+        Ok(Interval { lower, upper })
+    }
+
+    pub fn point(value: DataTypeValue) -> Result<Interval, String> {
+        Interval::new(value.clone(), value)
+    }
+
+    // "10..20", "30–40", "30—40" or "-5 - 5"
+    pub fn parse(s: &str) -> Option<Interval> {
+        let (lower, upper) = ["..", "\u{2013}", "\u{2014}"].iter()
+            .find_map(|separator| s.split_once(separator))
+            .or_else(|| {
+                let start = s.len() - s.trim_start().trim_start_matches(['-', '+']).len();
+                s[start..].find('-').map(|i| (&s[..start + i], &s[start + i + 1..]))
+            })?;
+        Interval::new(parse_bound(lower)?, parse_bound(upper)?).ok()
+    }
+
+    pub fn lower(&self) -> &DataTypeValue { &self.lower }
+
+    pub fn upper(&self) -> &DataTypeValue { &self.upper }
+
+    fn bounds(&self) -> (Numeric, Numeric) {
+        (self.lower.to_numeric().unwrap(), self.upper.to_numeric().unwrap())
+    }
+
+    pub fn width(&self) -> f64 {
+        let (lower, upper) = self.bounds();
+        lower.distance(&upper)
+    }
+
+    pub fn midpoint(&self) -> f64 {
+        let (lower, upper) = self.bounds();
+        lower.to_f64() + lower.distance(&upper) / 2.0
+    }
+
+    pub fn contains(&self, value: &DataTypeValue) -> bool {
+        let (lower, upper) = self.bounds();
+        match value.to_numeric() {
+            Some(v) => lower <= v && v <= upper,
+            None => false
+        }
+    }
+
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        self.contains(&other.lower) && self.contains(&other.upper)
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        let ((lower, upper), (other_lower, other_upper)) = (self.bounds(), other.bounds());
+        lower <= other_upper && other_lower <= upper
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        if !self.overlaps(other) { return None }
+        let ((lower, upper), (other_lower, other_upper)) = (self.bounds(), other.bounds());
+        let lower = if lower >= other_lower { &self.lower } else { &other.lower };
+        let upper = if upper <= other_upper { &self.upper } else { &other.upper };
+        Some(Interval { lower: lower.clone(), upper: upper.clone() })
+    }
+
+    // 0 inside, otherwise the distance to the nearest bound, NaN for non-numeric values
+    pub fn distance_to_value(&self, value: &DataTypeValue) -> f64 {
+        let v = match value.to_numeric() { Some(v) if !v.is_nan() => v, _ => return f64::NAN };
+        let (lower, upper) = self.bounds();
+        if v < lower {
+            v.distance(&lower)
+        } else if v > upper {
+            v.distance(&upper)
+        } else {
+            0.0
+        }
+    }
+
+    // stored values falling inside the interval
+    pub fn select<'a, I>(&self, values: I) -> Vec<&'a DataTypeValue>
+    where I: IntoIterator<Item = &'a DataTypeValue> {
+        values.into_iter().filter(|v| self.contains(v)).collect()
+    }
+}
+
+impl PartialEq for Interval {
+    fn eq(&self, other: &Interval) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+// by lower bound, then upper bound
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Interval) -> Option<Ordering> {
+        let ((lower, upper), (other_lower, other_upper)) = (self.bounds(), other.bounds());
+        match lower.partial_cmp(&other_lower)? {
+            Ordering::Equal => upper.partial_cmp(&other_upper),
+            ordering => Some(ordering)
+        }
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "[{}, {}]", self.lower, self.upper)
+    }
+}
+
+// Hausdorff distance, the larger of the bound distances
+impl Distance for Interval {
+    fn distance(&self, v: &Interval) -> f64 {
+        let ((lower, upper), (other_lower, other_upper)) = (self.bounds(), v.bounds());
+        lower.distance(&other_lower).max(upper.distance(&other_upper))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::Interval;
+    use crate::{
+        data::{ DataCategory, DataTypeValue, DataDeductor },
+        sensor::SensorData
+    };
+
+    #[test]
+    fn containment_and_overlap() {
+        let ages = Interval::new(DataTypeValue::U8(30), DataTypeValue::U8(40)).unwrap();
+        assert!(ages.contains(&DataTypeValue::I64(30)));
+        assert!(ages.contains(&DataTypeValue::F64(39.5)));
+        assert!(!ages.contains(&DataTypeValue::F32(40.5)));
+        assert!(!ages.contains(&DataTypeValue::String("35".to_string())));
+
+        let stored = [DataTypeValue::U8(20), DataTypeValue::U8(35), DataTypeValue::F64(40.0)];
+        assert_eq!(ages.select(&stored), vec![&stored[1], &stored[2]]);
+
+        let prices = Interval::parse("10..20").unwrap();
+        let other = Interval::parse("15 \u{2013} 25.5").unwrap();
+        assert!(prices.overlaps(&other) && !prices.contains_interval(&other));
+        assert_eq!(prices.intersection(&other).unwrap().to_string(), "[I64(15), I64(20)]");
+        assert_eq!(Interval::parse("-5 - -1").unwrap().width(), 4.0);
+        assert_eq!(other.midpoint(), 20.25);
+
+        assert!(Interval::new(DataTypeValue::U8(2), DataTypeValue::I8(1)).is_err());
+        assert!(Interval::new(DataTypeValue::Bool(true), DataTypeValue::U8(1)).is_err());
+        assert!(Interval::parse("a..b").is_none());
+    }
+
+    #[test]
+    fn hausdorff_distance() {
+        let lhs = Interval::parse("0..10").unwrap();
+        let rhs = Interval::new(DataTypeValue::F64(2.0), DataTypeValue::U8(15)).unwrap();
+        assert_eq!(SensorData::distance(&lhs, &rhs), 5.0);
+        assert_eq!(lhs.distance_to_value(&DataTypeValue::I8(-3)), 3.0);
+        assert_eq!(lhs.distance_to_value(&DataTypeValue::U8(7)), 0.0);
+        assert!(lhs.distance_to_value(&DataTypeValue::Bool(true)).is_nan());
+        let same = Interval::new(DataTypeValue::F32(0.0), DataTypeValue::U8(10)).unwrap();
+        assert!(lhs.equals(&same));
+        assert!(lhs < rhs);
+        assert_eq!(rhs.data_category(), DataCategory::Numerical);
+    }
+}
@@ -1,10 +1,31 @@
 #![feature(negative_impls)]
 #![feature(auto_traits)]
+#![feature(allow_internal_unstable)]
+#![allow(internal_features)]
 
 pub mod sensor;
 pub mod distances;
+pub mod metric;
+pub mod similarity;
 pub mod data;
 pub mod algorithms;
 pub mod neuron;
 pub mod connection;
-pub mod polars;
\ No newline at end of file
+pub mod polars;
+pub mod temporal;
+pub mod ordinal;
+pub mod numeric;
+pub mod ordered;
+pub mod inference;
+pub mod arithmetic;
+pub mod cast;
+pub mod codec;
+pub mod column;
+pub mod interner;
+pub mod parsing;
+pub mod geo;
+pub mod embedding;
+pub mod taxonomy;
+pub mod interval;
+pub mod statistics;
+pub mod text;
@@ -0,0 +1,169 @@
+use num_traits::ToPrimitive;
+
+use crate::{ distances::Distance, sensor::SensorData };
+
+// notion of closeness chosen per sensor instead of per type
+pub trait Metric<T: ?Sized> {
+    fn distance(&self, lhs: &T, rhs: &T) -> f64;
+}
+
+impl<T: ?Sized, F: Fn(&T, &T) -> f64> Metric<T> for F {
+    fn distance(&self, lhs: &T, rhs: &T) -> f64 { self(lhs, rhs) }
+}
+
+fn to_f64<T: ToPrimitive>(value: &T) -> f64 { value.to_f64().unwrap_or(f64::NAN) }
+
+// the Distance implementation of the type
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Natural;
+
+impl<T: Distance + ?Sized> Metric<T> for Natural {
+    fn distance(&self, lhs: &T, rhs: &T) -> f64 { lhs.distance(rhs) }
+}
+
+// SensorData::distance, the metric of sensors that do not attach one
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct SensorDistance;
+
+impl<T: SensorData> Metric<T> for SensorDistance {
+    fn distance(&self, lhs: &T, rhs: &T) -> f64 { SensorData::distance(lhs, rhs) }
+}
+
+// |a - b|
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Absolute;
+
+impl<T: ToPrimitive> Metric<T> for Absolute {
+    fn distance(&self, lhs: &T, rhs: &T) -> f64 { (to_f64(lhs) - to_f64(rhs)).abs() }
+}
+
+// (a - b)^2
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Squared;
+
+impl<T: ToPrimitive> Metric<T> for Squared {
+    fn distance(&self, lhs: &T, rhs: &T) -> f64 { (to_f64(lhs) - to_f64(rhs)).powi(2) }
+}
+
+// |a - b| / max(|a|, |b|) in [0, 2], 0 when both are 0
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Relative;
+
+impl<T: ToPrimitive> Metric<T> for Relative {
+    fn distance(&self, lhs: &T, rhs: &T) -> f64 {
+        let (lhs, rhs) = (to_f64(lhs), to_f64(rhs));
+        let scale = lhs.abs().max(rhs.abs());
+        if scale == 0.0 { 0.0 } else { (lhs - rhs).abs() / scale }
+    }
+}
+
+// |ln(a / b)|, NaN unless both are positive
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct LogRatio;
+
+impl<T: ToPrimitive> Metric<T> for LogRatio {
+    fn distance(&self, lhs: &T, rhs: &T) -> f64 {
+        let (lhs, rhs) = (to_f64(lhs), to_f64(rhs));
+        if lhs > 0.0 && rhs > 0.0 { (lhs.ln() - rhs.ln()).abs() } else { f64::NAN }
+    }
+}
+
+// largest coordinate difference, NaN for mismatched dimensions
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Chebyshev;
+
+impl<T: ToPrimitive> Metric<[T]> for Chebyshev {
+    fn distance(&self, lhs: &[T], rhs: &[T]) -> f64 {
+        if lhs.len() != rhs.len() { return f64::NAN }
+        lhs.iter().zip(rhs).map(|(a, b)| (to_f64(a) - to_f64(b)).abs()).fold(0.0, f64::max)
+    }
+}
+
+impl<T: ToPrimitive> Metric<Vec<T>> for Chebyshev {
+    fn distance(&self, lhs: &Vec<T>, rhs: &Vec<T>) -> f64 {
+        Metric::<[T]>::distance(self, lhs, rhs)
+    }
+}
+
+// (sum |a_i - b_i|^p)^(1/p), p = 1 is Manhattan and p = 2 Euclidean,
+// NaN for mismatched dimensions
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Minkowski {
+    p: f64
+}
+
+impl Minkowski {
+    // None for p < 1, which does not satisfy the triangle inequality
+    pub fn new(p: f64) -> Option<Minkowski> {
+        if p >= 1.0 { Some(Minkowski { p }) } else { None }
+    }
+
+    pub fn manhattan() -> Minkowski { Minkowski { p: 1.0 } }
+
+    pub fn euclidean() -> Minkowski { Minkowski { p: 2.0 } }
+
+    pub fn p(&self) -> f64 { self.p }
+}
+
+impl<T: ToPrimitive> Metric<[T]> for Minkowski {
+    fn distance(&self, lhs: &[T], rhs: &[T]) -> f64 {
+        if self.p.is_infinite() { return Metric::<[T]>::distance(&Chebyshev, lhs, rhs) }
+        if lhs.len() != rhs.len() { return f64::NAN }
+        lhs.iter().zip(rhs)
+            .map(|(a, b)| (to_f64(a) - to_f64(b)).abs().powf(self.p))
+            .sum::<f64>()
+            .powf(1.0 / self.p)
+    }
+}
+
+impl<T: ToPrimitive> Metric<Vec<T>> for Minkowski {
+    fn distance(&self, lhs: &Vec<T>, rhs: &Vec<T>) -> f64 {
+        Metric::<[T]>::distance(self, lhs, rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{
+        Metric, SensorDistance, Natural, Absolute, Squared, Relative, LogRatio, Chebyshev, Minkowski
+    };
+
+    #[test]
+    fn scalar_metrics() {
+        // two sensors over f64 with different notions of closeness
+        let prices: Rc<dyn Metric<f64>> = Rc::new(Relative);
+        let temperatures: Rc<dyn Metric<f64>> = Rc::new(Absolute);
+        assert_eq!(prices.distance(&100.0, &110.0), 10.0 / 110.0);
+        assert_eq!(temperatures.distance(&100.0, &110.0), 10.0);
+
+        assert_eq!(Squared.distance(&3i32, &-1i32), 16.0);
+        assert_eq!(Relative.distance(&0u8, &0u8), 0.0);
+        assert!((LogRatio.distance(&10.0, &1000.0) - 100f64.ln()).abs() < 1e-12);
+        assert!(LogRatio.distance(&0.0, &1.0).is_nan());
+        assert_eq!(Natural.distance("a", "b"), 1.0);
+
+        let default: &dyn Metric<String> = &SensorDistance;
+        assert_eq!(default.distance(&"a".to_string(), &"b".to_string()), 1.0);
+        assert_eq!(SensorDistance.distance(&1.5f64, &4.0), 2.5);
+
+        let halved = |lhs: &f64, rhs: &f64| (lhs - rhs).abs() / 2.0;
+        assert_eq!(Metric::distance(&halved, &1.0, &4.0), 1.5);
+    }
+
+    #[test]
+    fn multidimensional_metrics() {
+        let (lhs, rhs) = (vec![0.0, 0.0, 0.0], vec![3.0, -4.0, 1.0]);
+        assert_eq!(Chebyshev.distance(&lhs, &rhs), 4.0);
+        assert_eq!(Minkowski::manhattan().distance(&lhs, &rhs), 8.0);
+        assert_eq!(Minkowski::euclidean().distance(&lhs[..2], &rhs[..2]), 5.0);
+        assert_eq!(Minkowski::new(f64::INFINITY).unwrap().distance(&lhs, &rhs), 4.0);
+        assert!(Minkowski::new(0.5).is_none());
+        assert!(Chebyshev.distance(&lhs, &rhs[..2].to_vec()).is_nan());
+        let cubic = Minkowski::new(3.0).unwrap().distance(&[1u8, 2][..], &[2u8, 4][..]);
+        assert!((cubic - 9f64.cbrt()).abs() < 1e-12);
+    }
+}
@@ -8,10 +8,12 @@ use std::{
 
 use crate::{
     connection::{ Connection, ConnectionKind }, 
-    data::{ DataTypeValue, DataType }
+    data::{ DataTypeValue, DataType },
+    interner::StringInterner
 };
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NeuronID {
     pub id: Rc<str>,
     pub parent_id: Rc<str>
@@ -21,6 +23,10 @@ impl NeuronID {
     pub fn new(id: &str, parent_id: &str) -> NeuronID {
         NeuronID { id: Rc::from(id), parent_id: Rc::from(parent_id) }
     }
+
+    pub fn new_interned(id: &str, parent_id: &str, interner: &mut StringInterner) -> NeuronID {
+        NeuronID { id: interner.intern(id), parent_id: interner.intern(parent_id) }
+    }
 }
 
 impl Display for NeuronID {
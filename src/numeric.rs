@@ -0,0 +1,349 @@
+use std::cmp::Ordering;
+
+use crate::data::{ DataType, DataTypeValue };
+
+pub(crate) const TWO_POW_127: f64 = 170_141_183_460_469_231_731_687_303_715_884_105_728.0;
+pub(crate) const TWO_POW_128: f64 = 340_282_366_920_938_463_463_374_607_431_768_211_456.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum PromotionMode {
+    #[default]
+    Promote,
+    Strict
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum Numeric {
+    Signed(i128),
+    Unsigned(u128),
+    Float(f64)
+}
+
+impl Numeric {
+    pub fn from_value(value: &DataTypeValue) -> Option<Numeric> {
+        match value {
+            DataTypeValue::U8(v) => Some(Numeric::Unsigned(*v as u128)),
+            DataTypeValue::U16(v) => Some(Numeric::Unsigned(*v as u128)),
+            DataTypeValue::U32(v) => Some(Numeric::Unsigned(*v as u128)),
+            DataTypeValue::U64(v) => Some(Numeric::Unsigned(*v as u128)),
+            DataTypeValue::U128(v) => Some(Numeric::Unsigned(*v)),
+            DataTypeValue::USize(v) => Some(Numeric::Unsigned(*v as u128)),
+            DataTypeValue::I8(v) => Some(Numeric::Signed(*v as i128)),
+            DataTypeValue::I16(v) => Some(Numeric::Signed(*v as i128)),
+            DataTypeValue::I32(v) => Some(Numeric::Signed(*v as i128)),
+            DataTypeValue::I64(v) => Some(Numeric::Signed(*v as i128)),
+            DataTypeValue::I128(v) => Some(Numeric::Signed(*v)),
+            DataTypeValue::ISize(v) => Some(Numeric::Signed(*v as i128)),
+            DataTypeValue::F32(v) => Some(Numeric::Float(*v as f64)),
+            DataTypeValue::F64(v) => Some(Numeric::Float(*v)),
+            _ => None
+        }
+    }
+
+    pub fn is_nan(&self) -> bool {
+        matches!(self, Numeric::Float(v) if v.is_nan())
+    }
+
+    // exact conversion for integer targets, floats are rounded to the nearest representable value
+    pub fn to_value(&self, data_type: &DataType) -> Option<DataTypeValue> {
+        macro_rules! integer {
+            ($variant:ident) => {
+                match self {
+                    Numeric::Signed(v) => DataTypeValue::$variant((*v).try_into().ok()?),
+                    Numeric::Unsigned(v) => DataTypeValue::$variant((*v).try_into().ok()?),
+                    Numeric::Float(v) => {
+                        if v.fract() != 0.0 { return None }
+                        let integer = Numeric::from_float_integer(*v)?;
+                        return integer.to_value(data_type)
+                    }
+                }
+            }
+        }
+
+        let value = match data_type {
+            DataType::U8 => integer!(U8),
+            DataType::U16 => integer!(U16),
+            DataType::U32 => integer!(U32),
+            DataType::U64 => integer!(U64),
+            DataType::U128 => integer!(U128),
+            DataType::USize => integer!(USize),
+            DataType::I8 => integer!(I8),
+            DataType::I16 => integer!(I16),
+            DataType::I32 => integer!(I32),
+            DataType::I64 => integer!(I64),
+            DataType::I128 => integer!(I128),
+            DataType::ISize => integer!(ISize),
+            DataType::F32 => DataTypeValue::F32(self.to_f64() as f32),
+            DataType::F64 => DataTypeValue::F64(self.to_f64()),
+            _ => return None
+        };
+        Some(value)
+    }
+
+    fn from_float_integer(v: f64) -> Option<Numeric> {
+        if (-TWO_POW_127..TWO_POW_127).contains(&v) {
+            Some(Numeric::Signed(v as i128))
+        } else if (0.0..TWO_POW_128).contains(&v) {
+            Some(Numeric::Unsigned(v as u128))
+        } else {
+            None
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Numeric::Signed(v) => *v as f64,
+            Numeric::Unsigned(v) => *v as f64,
+            Numeric::Float(v) => *v
+        }
+    }
+
+    // None when a float is involved or the values are more than u128::MAX apart
+    pub fn exact_distance(&self, other: &Numeric) -> Option<u128> {
+        match (self, other) {
+            (Numeric::Signed(lhs), Numeric::Signed(rhs)) => Some(lhs.abs_diff(*rhs)),
+            (Numeric::Unsigned(lhs), Numeric::Unsigned(rhs)) => Some(lhs.abs_diff(*rhs)),
+            (Numeric::Signed(signed), Numeric::Unsigned(unsigned))
+            | (Numeric::Unsigned(unsigned), Numeric::Signed(signed)) => {
+                if *signed >= 0 {
+                    Some((*signed as u128).abs_diff(*unsigned))
+                } else {
+                    unsigned.checked_add(signed.unsigned_abs())
+                }
+            }
+            _ => None
+        }
+    }
+
+    pub fn distance(&self, other: &Numeric) -> f64 {
+        match (self.exact_distance(other), self, other) {
+            (Some(d), _, _) => d as f64,
+            // only mixed signs can be more than u128::MAX apart
+            (None, Numeric::Signed(signed), Numeric::Unsigned(unsigned))
+            | (None, Numeric::Unsigned(unsigned), Numeric::Signed(signed)) => {
+                *unsigned as f64 + signed.unsigned_abs() as f64
+            }
+            _ => (self.to_f64() - other.to_f64()).abs()
+        }
+    }
+}
+
+fn compare_signed_float(lhs: i128, rhs: f64) -> Option<Ordering> {
+    if rhs.is_nan() { return None }
+    if rhs >= TWO_POW_127 { return Some(Ordering::Less) }
+    if rhs < -TWO_POW_127 { return Some(Ordering::Greater) }
+    let truncated = rhs.trunc();
+    match lhs.cmp(&(truncated as i128)) {
+        Ordering::Equal => 0.0.partial_cmp(&(rhs - truncated)),
+        ordering => Some(ordering)
+    }
+}
+
+fn compare_unsigned_float(lhs: u128, rhs: f64) -> Option<Ordering> {
+    if rhs.is_nan() { return None }
+    if rhs < 0.0 { return Some(Ordering::Greater) }
+    if rhs >= TWO_POW_128 { return Some(Ordering::Less) }
+    let truncated = rhs.trunc();
+    match lhs.cmp(&(truncated as u128)) {
+        Ordering::Equal => 0.0.partial_cmp(&(rhs - truncated)),
+        ordering => Some(ordering)
+    }
+}
+
+impl PartialEq for Numeric {
+    fn eq(&self, other: &Numeric) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Numeric {
+    fn partial_cmp(&self, other: &Numeric) -> Option<Ordering> {
+        match (self, other) {
+            (Numeric::Signed(lhs), Numeric::Signed(rhs)) => Some(lhs.cmp(rhs)),
+            (Numeric::Unsigned(lhs), Numeric::Unsigned(rhs)) => Some(lhs.cmp(rhs)),
+            (Numeric::Float(lhs), Numeric::Float(rhs)) => lhs.partial_cmp(rhs),
+            (Numeric::Signed(lhs), Numeric::Unsigned(rhs)) => {
+                if *lhs < 0 { Some(Ordering::Less) } else { Some((*lhs as u128).cmp(rhs)) }
+            }
+            (Numeric::Unsigned(_), Numeric::Signed(_)) => {
+                other.partial_cmp(self).map(Ordering::reverse)
+            }
+            (Numeric::Signed(lhs), Numeric::Float(rhs)) => compare_signed_float(*lhs, *rhs),
+            (Numeric::Unsigned(lhs), Numeric::Float(rhs)) => compare_unsigned_float(*lhs, *rhs),
+            (Numeric::Float(_), _) => other.partial_cmp(self).map(Ordering::reverse)
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum NumericKind { Signed, Unsigned, Float }
+
+fn numeric_kind(data_type: &DataType) -> Option<(NumericKind, u32)> {
+    let kind = match data_type {
+        DataType::U8 => (NumericKind::Unsigned, 8),
+        DataType::U16 => (NumericKind::Unsigned, 16),
+        DataType::U32 => (NumericKind::Unsigned, 32),
+        DataType::U64 => (NumericKind::Unsigned, 64),
+        DataType::U128 => (NumericKind::Unsigned, 128),
+        DataType::USize => (NumericKind::Unsigned, usize::BITS),
+        DataType::I8 => (NumericKind::Signed, 8),
+        DataType::I16 => (NumericKind::Signed, 16),
+        DataType::I32 => (NumericKind::Signed, 32),
+        DataType::I64 => (NumericKind::Signed, 64),
+        DataType::I128 => (NumericKind::Signed, 128),
+        DataType::ISize => (NumericKind::Signed, isize::BITS),
+        DataType::F32 => (NumericKind::Float, 32),
+        DataType::F64 => (NumericKind::Float, 64),
+        _ => return None
+    };
+    Some(kind)
+}
+
+fn numeric_type(kind: NumericKind, bits: u32) -> DataType {
+    match (kind, bits) {
+        (NumericKind::Unsigned, 8) => DataType::U8,
+        (NumericKind::Unsigned, 16) => DataType::U16,
+        (NumericKind::Unsigned, 32) => DataType::U32,
+        (NumericKind::Unsigned, 64) => DataType::U64,
+        (NumericKind::Unsigned, _) => DataType::U128,
+        (NumericKind::Signed, 8) => DataType::I8,
+        (NumericKind::Signed, 16) => DataType::I16,
+        (NumericKind::Signed, 32) => DataType::I32,
+        (NumericKind::Signed, 64) => DataType::I64,
+        (NumericKind::Signed, _) => DataType::I128,
+        (NumericKind::Float, 32) => DataType::F32,
+        (NumericKind::Float, _) => DataType::F64
+    }
+}
+
+// the narrowest type both numeric types can be converted into,
+// u128 mixed with any signed type and wide integers mixed with floats end up as f64
+pub fn promote(lhs: &DataType, rhs: &DataType) -> Option<DataType> {
+    let (lhs_kind, lhs_bits) = numeric_kind(lhs)?;
+    let (rhs_kind, rhs_bits) = numeric_kind(rhs)?;
+    match (lhs, rhs) {
+        (DataType::USize, DataType::USize) => return Some(DataType::USize),
+        (DataType::ISize, DataType::ISize) => return Some(DataType::ISize),
+        _ => {}
+    }
+    let promoted = match (lhs_kind, rhs_kind) {
+        (NumericKind::Float, _) | (_, NumericKind::Float) => {
+            let integer_bits = match (lhs_kind, rhs_kind) {
+                (NumericKind::Float, NumericKind::Float) => 0,
+                (NumericKind::Float, _) => rhs_bits,
+                _ => lhs_bits
+            };
+            if lhs_bits.max(rhs_bits) <= 32 && integer_bits <= 16 {
+                DataType::F32
+            } else {
+                DataType::F64
+            }
+        }
+        (lhs_kind, rhs_kind) if lhs_kind == rhs_kind => {
+            numeric_type(lhs_kind, lhs_bits.max(rhs_bits))
+        }
+        _ => {
+            let (signed_bits, unsigned_bits) = if lhs_kind == NumericKind::Signed {
+                (lhs_bits, rhs_bits)
+            } else {
+                (rhs_bits, lhs_bits)
+            };
+            if unsigned_bits < signed_bits {
+                numeric_type(NumericKind::Signed, signed_bits)
+            } else if unsigned_bits < 128 {
+                numeric_type(NumericKind::Signed, unsigned_bits * 2)
+            } else {
+                DataType::F64
+            }
+        }
+    };
+    Some(promoted)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{ promote, PromotionMode };
+    use crate::{
+        data::{ DataType, DataTypeValue },
+        distances::Distance
+    };
+
+    #[test]
+    fn promoted_comparison() {
+        assert_eq!(DataTypeValue::U8(3), DataTypeValue::I32(3));
+        assert!(DataTypeValue::I8(-1) < DataTypeValue::U128(0));
+        assert!(DataTypeValue::U128(u128::MAX) > DataTypeValue::I128(i128::MAX));
+        assert!(DataTypeValue::I64(2) < DataTypeValue::F32(2.5));
+        assert!(DataTypeValue::U64(u64::MAX) > DataTypeValue::F64(u64::MAX as f64 - 4096.0));
+        assert_eq!(
+            DataTypeValue::U64(u64::MAX).partial_cmp(&DataTypeValue::F64(u64::MAX as f64)),
+            Some(Ordering::Less)
+        );
+        assert!(DataTypeValue::I8(0).partial_cmp(&DataTypeValue::F64(f64::NAN)).is_none());
+        assert!(DataTypeValue::Bool(true) != DataTypeValue::U8(1));
+    }
+
+    #[test]
+    fn promoted_distance() {
+        assert_eq!(DataTypeValue::U8(3).distance(&DataTypeValue::I32(-2)), 5.0);
+        assert_eq!(
+            DataTypeValue::I128(i128::MIN).distance(&DataTypeValue::I128(i128::MAX)),
+            u128::MAX as f64
+        );
+        assert_eq!(
+            DataTypeValue::I128(-1).distance(&DataTypeValue::U128(u128::MAX)),
+            u128::MAX as f64
+        );
+        assert_eq!(DataTypeValue::F32(0.5).distance(&DataTypeValue::U16(2)), 1.5);
+    }
+
+    #[test]
+    fn exact_distance_boundaries() {
+        let exact = |lhs: DataTypeValue, rhs: DataTypeValue| lhs.exact_distance(&rhs);
+        let id = 1u64 << 60;
+        assert_eq!(DataTypeValue::U64(id).distance(&DataTypeValue::U64(id + 1)), 1.0);
+        assert_eq!(exact(DataTypeValue::U64(id), DataTypeValue::I64(id as i64 + 1)), Some(1));
+        assert_eq!(exact(DataTypeValue::U64(u64::MAX), DataTypeValue::I64(i64::MIN)), Some(
+            u64::MAX as u128 + (1 << 63)
+        ));
+        assert_eq!(
+            exact(DataTypeValue::I128(i128::MIN), DataTypeValue::U128(i128::MAX as u128)),
+            Some(u128::MAX)
+        );
+        assert_eq!(exact(DataTypeValue::I128(-1), DataTypeValue::U128(u128::MAX)), None);
+        assert_eq!(exact(DataTypeValue::U128(u128::MAX), DataTypeValue::USize(0)), Some(u128::MAX));
+        assert_eq!(exact(DataTypeValue::ISize(isize::MIN), DataTypeValue::I8(0)), Some(1 << 63));
+        assert_eq!(exact(DataTypeValue::F64(1.0), DataTypeValue::U8(1)), None);
+        assert_eq!(exact(DataTypeValue::Bool(true), DataTypeValue::U8(1)), None);
+        assert_eq!(
+            DataTypeValue::I128(i128::MIN).distance(&DataTypeValue::U128(u128::MAX)),
+            u128::MAX as f64 + 2f64.powi(127)
+        );
+    }
+
+    #[test]
+    fn strict_mode() {
+        let lhs = DataTypeValue::U8(3);
+        let rhs = DataTypeValue::I32(3);
+        assert!(!lhs.eq_with(&rhs, PromotionMode::Strict));
+        assert_eq!(lhs.partial_cmp_with(&rhs, PromotionMode::Strict), Some(Ordering::Less));
+        assert!(lhs.distance_with(&rhs, PromotionMode::Strict).is_nan());
+        assert!(lhs.eq_with(&DataTypeValue::U8(3), PromotionMode::Strict));
+    }
+
+    #[test]
+    fn promotion_lattice() {
+        assert!(matches!(promote(&DataType::U8, &DataType::I8), Some(DataType::I16)));
+        assert!(matches!(promote(&DataType::U16, &DataType::I64), Some(DataType::I64)));
+        assert!(matches!(promote(&DataType::U64, &DataType::I32), Some(DataType::I128)));
+        assert!(matches!(promote(&DataType::U128, &DataType::I8), Some(DataType::F64)));
+        assert!(matches!(promote(&DataType::I16, &DataType::F32), Some(DataType::F32)));
+        assert!(matches!(promote(&DataType::I32, &DataType::F32), Some(DataType::F64)));
+        assert!(matches!(promote(&DataType::USize, &DataType::USize), Some(DataType::USize)));
+        assert!(promote(&DataType::Bool, &DataType::U8).is_none());
+    }
+}
@@ -0,0 +1,201 @@
+use std::{
+    cmp::Ordering,
+    hash::{ Hash, Hasher },
+    fmt::{ Display, Formatter, Result as FmtResult }
+};
+
+use crate::{
+    data::DataTypeValue,
+    numeric::{ Numeric, TWO_POW_127, TWO_POW_128 }
+};
+
+// total order over all DataTypeValue variants:
+// Bool < numeric < RcStr < String < Date < Datetime < Duration < Ordinal < Unknown,
+// numeric variants are compared by promoted value, all NaNs are equal and greater than any number,
+// -0.0 equals 0.0
+#[derive(Debug, Clone)]
+pub struct OrderedDataTypeValue(pub DataTypeValue);
+
+#[derive(Hash)]
+enum CanonicalNumber {
+    Integer(i128),
+    LargeUnsigned(u128),
+    Float(u64),
+    NaN
+}
+
+fn canonical_number(number: Numeric) -> CanonicalNumber {
+    match number {
+        Numeric::Signed(v) => CanonicalNumber::Integer(v),
+        Numeric::Unsigned(v) => match i128::try_from(v) {
+            Ok(v) => CanonicalNumber::Integer(v),
+            Err(_) => CanonicalNumber::LargeUnsigned(v)
+        },
+        Numeric::Float(v) => {
+            if v.is_nan() {
+                CanonicalNumber::NaN
+            } else if v.fract() == 0.0 && (-TWO_POW_127..TWO_POW_127).contains(&v) {
+                CanonicalNumber::Integer(v as i128)
+            } else if v.fract() == 0.0 && (0.0..TWO_POW_128).contains(&v) {
+                CanonicalNumber::LargeUnsigned(v as u128)
+            } else {
+                CanonicalNumber::Float(v.to_bits())
+            }
+        }
+    }
+}
+
+fn numeric_total_cmp(lhs: &Numeric, rhs: &Numeric) -> Ordering {
+    match (lhs.is_nan(), rhs.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => lhs.partial_cmp(rhs).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl OrderedDataTypeValue {
+    pub fn new(value: DataTypeValue) -> OrderedDataTypeValue { OrderedDataTypeValue(value) }
+
+    pub fn value(&self) -> &DataTypeValue { &self.0 }
+
+    pub fn into_inner(self) -> DataTypeValue { self.0 }
+
+    fn group(&self) -> u8 {
+        if self.0.to_numeric().is_some() { return 1 }
+        match &self.0 {
+            DataTypeValue::Bool(_) => 0,
+            DataTypeValue::RcStr(_) => 2,
+            DataTypeValue::String(_) => 3,
+            DataTypeValue::Date(_) => 4,
+            DataTypeValue::Datetime(_) => 5,
+            DataTypeValue::Duration(_) => 6,
+            DataTypeValue::Ordinal(_) => 7,
+            _ => 8
+        }
+    }
+}
+
+impl From<DataTypeValue> for OrderedDataTypeValue {
+    fn from(v: DataTypeValue) -> OrderedDataTypeValue { OrderedDataTypeValue(v) }
+}
+
+impl From<OrderedDataTypeValue> for DataTypeValue {
+    fn from(v: OrderedDataTypeValue) -> DataTypeValue { v.0 }
+}
+
+impl PartialEq for OrderedDataTypeValue {
+    fn eq(&self, other: &OrderedDataTypeValue) -> bool { self.cmp(other) == Ordering::Equal }
+}
+
+impl Eq for OrderedDataTypeValue {}
+
+impl PartialOrd for OrderedDataTypeValue {
+    fn partial_cmp(&self, other: &OrderedDataTypeValue) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrderedDataTypeValue {
+    fn cmp(&self, other: &OrderedDataTypeValue) -> Ordering {
+        let group_ordering = self.group().cmp(&other.group());
+        if group_ordering != Ordering::Equal { return group_ordering }
+
+        if let (Some(lhs), Some(rhs)) = (self.0.to_numeric(), other.0.to_numeric()) {
+            return numeric_total_cmp(&lhs, &rhs)
+        }
+
+        match (&self.0, &other.0) {
+            (DataTypeValue::Bool(lhs), DataTypeValue::Bool(rhs)) => lhs.cmp(rhs),
+            (DataTypeValue::RcStr(lhs), DataTypeValue::RcStr(rhs)) => lhs.cmp(rhs),
+            (DataTypeValue::String(lhs), DataTypeValue::String(rhs)) => lhs.cmp(rhs),
+            (DataTypeValue::Date(lhs), DataTypeValue::Date(rhs)) => lhs.cmp(rhs),
+            (DataTypeValue::Datetime(lhs), DataTypeValue::Datetime(rhs)) => lhs.cmp(rhs),
+            (DataTypeValue::Duration(lhs), DataTypeValue::Duration(rhs)) => lhs.cmp(rhs),
+            (DataTypeValue::Ordinal(lhs), DataTypeValue::Ordinal(rhs)) => {
+                lhs.scale().levels().cmp(rhs.scale().levels()).then(lhs.rank().cmp(&rhs.rank()))
+            }
+            _ => Ordering::Equal
+        }
+    }
+}
+
+impl Hash for OrderedDataTypeValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.group().hash(state);
+        if let Some(number) = self.0.to_numeric() {
+            return canonical_number(number).hash(state)
+        }
+
+        match &self.0 {
+            DataTypeValue::Bool(v) => v.hash(state),
+            DataTypeValue::RcStr(v) => v.hash(state),
+            DataTypeValue::String(v) => v.hash(state),
+            DataTypeValue::Date(v) => v.hash(state),
+            DataTypeValue::Datetime(v) => v.hash(state),
+            DataTypeValue::Duration(v) => v.hash(state),
+            DataTypeValue::Ordinal(v) => {
+                v.scale().levels().hash(state);
+                v.rank().hash(state)
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Display for OrderedDataTypeValue {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        rc::Rc,
+        collections::{ BTreeSet, HashMap }
+    };
+
+    use pretty_assertions::assert_eq;
+
+    use super::OrderedDataTypeValue;
+    use crate::data::DataTypeValue;
+
+    #[test]
+    fn hash_key() {
+        let mut counts: HashMap<OrderedDataTypeValue, usize> = HashMap::new();
+        for v in [
+            DataTypeValue::U8(3), DataTypeValue::I64(3), DataTypeValue::F32(3.0),
+            DataTypeValue::F64(f64::NAN), DataTypeValue::F32(f32::NAN),
+            DataTypeValue::F64(-0.0), DataTypeValue::U128(0),
+            DataTypeValue::RcStr(Rc::from("a")), DataTypeValue::String("a".to_string())
+        ] {
+            *counts.entry(v.into()).or_default() += 1;
+        }
+        assert_eq!(counts.len(), 5);
+        assert_eq!(counts[&DataTypeValue::U16(3).into()], 3);
+        assert_eq!(counts[&DataTypeValue::F64(f64::NAN).into()], 2);
+        assert_eq!(counts[&DataTypeValue::I8(0).into()], 2);
+    }
+
+    #[test]
+    fn total_order() {
+        let values: BTreeSet<OrderedDataTypeValue> = [
+            DataTypeValue::Unknown,
+            DataTypeValue::String("b".to_string()),
+            DataTypeValue::F64(f64::NAN),
+            DataTypeValue::I8(-5),
+            DataTypeValue::U128(u128::MAX),
+            DataTypeValue::F32(0.5),
+            DataTypeValue::Bool(true),
+            DataTypeValue::RcStr(Rc::from("z"))
+        ].into_iter().map(OrderedDataTypeValue::from).collect();
+
+        let sorted: Vec<String> = values.into_iter().map(|x| x.to_string()).collect();
+        assert_eq!(sorted, vec![
+            "Bool(true)", "I8(-5)", "F32(0.5)",
+            &format!("U128({})", u128::MAX), "F64(NaN)",
+            "RcStr(\"z\")", "String(\"b\")", "Unknown"
+        ]);
+    }
+}
@@ -0,0 +1,167 @@
+use std::{
+    rc::Rc,
+    cmp::Ordering,
+    fmt::{ Display, Formatter, Result as FmtResult }
+};
+
+use crate::distances::Distance;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "OrdinalScaleFields"))]
+pub struct OrdinalScale {
+    levels: Vec<Rc<str>>
+}
+
+impl OrdinalScale {
+    pub fn new(levels: &[&str]) -> Result<Rc<OrdinalScale>, String> {
+        if levels.is_empty() { return Err("ordinal scale needs at least one level".to_string()) }
+        for (i, level) in levels.iter().enumerate() {
+            if levels[..i].contains(level) {
+                return Err(format!("duplicated ordinal level {}", level))
+            }
+        }
+        Ok(Rc::new(OrdinalScale { levels: levels.iter().map(|level| Rc::from(*level)).collect() }))
+    }
+
+    pub fn levels(&self) -> &[Rc<str>] { &self.levels }
+
+    pub fn len(&self) -> usize { self.levels.len() }
+
+    pub fn is_empty(&self) -> bool { self.levels.is_empty() }
+
+    pub fn rank(&self, level: &str) -> Option<usize> {
+        self.levels.iter().position(|x| &**x == level)
+    }
+
+    pub fn value(self: &Rc<Self>, level: &str) -> Option<Ordinal> {
+        Ordinal::new(self, level)
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "OrdinalFields"))]
+pub struct Ordinal {
+    scale: Rc<OrdinalScale>,
+    rank: usize
+}
+
+// deserialized values go through the same checks as the constructors
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct OrdinalScaleFields {
+    levels: Vec<Rc<str>>
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<OrdinalScaleFields> for OrdinalScale {
+    type Error = String;
+
+    fn try_from(fields: OrdinalScaleFields) -> Result<OrdinalScale, String> {
+        let levels: Vec<&str> = fields.levels.iter().map(|x| &**x).collect();
+        OrdinalScale::new(&levels).map(Rc::unwrap_or_clone)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct OrdinalFields {
+    scale: Rc<OrdinalScale>,
+    rank: usize
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<OrdinalFields> for Ordinal {
+    type Error = String;
+
+    fn try_from(fields: OrdinalFields) -> Result<Ordinal, String> {
+        Ordinal::from_rank(&fields.scale, fields.rank).ok_or_else(|| {
+            format!("rank {} out of a scale of {} levels", fields.rank, fields.scale.len())
+        })
+    }
+}
+
+impl Ordinal {
+    pub fn new(scale: &Rc<OrdinalScale>, level: &str) -> Option<Ordinal> {
+        Some(Ordinal { scale: scale.clone(), rank: scale.rank(level)? })
+    }
+
+    pub fn from_rank(scale: &Rc<OrdinalScale>, rank: usize) -> Option<Ordinal> {
+        if rank < scale.len() { Some(Ordinal { scale: scale.clone(), rank }) } else { None }
+    }
+
+    pub fn scale(&self) -> &Rc<OrdinalScale> { &self.scale }
+
+    pub fn rank(&self) -> usize { self.rank }
+
+    pub fn level(&self) -> &str { &self.scale.levels[self.rank] }
+
+    pub fn normalized_rank(&self) -> f64 {
+        if self.scale.len() < 2 { 0.0 } else { self.rank as f64 / (self.scale.len() - 1) as f64 }
+    }
+
+    pub fn same_scale(&self, other: &Ordinal) -> bool {
+        Rc::ptr_eq(&self.scale, &other.scale) || self.scale == other.scale
+    }
+}
+
+impl PartialEq for Ordinal {
+    fn eq(&self, other: &Ordinal) -> bool {
+        self.rank == other.rank && self.same_scale(other)
+    }
+}
+
+impl PartialOrd for Ordinal {
+    fn partial_cmp(&self, other: &Ordinal) -> Option<Ordering> {
+        if self.same_scale(other) { Some(self.rank.cmp(&other.rank)) } else { None }
+    }
+}
+
+impl Display for Ordinal {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.level())
+    }
+}
+
+// rank difference normalized to [0, 1] over the whole scale
+impl Distance for Ordinal {
+    fn distance(&self, v: &Ordinal) -> f64 {
+        if !self.same_scale(v) { return f64::NAN }
+        (self.normalized_rank() - v.normalized_rank()).abs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{ Ordinal, OrdinalScale };
+    use crate::distances::Distance;
+
+    #[test]
+    fn ordinal_rank_order() {
+        let scale = OrdinalScale::new(&["low", "medium", "high"]).unwrap();
+        let low = scale.value("low").unwrap();
+        let high = scale.value("high").unwrap();
+        assert!(low < high);
+        assert_eq!(low.distance(&high), 1.0);
+        assert_eq!(scale.value("medium").unwrap().distance(&high), 0.5);
+        assert_eq!(high.to_string(), "high");
+        assert!(scale.value("extreme").is_none());
+        assert!(Ordinal::from_rank(&scale, 3).is_none());
+    }
+
+    #[test]
+    fn ordinal_scales() {
+        assert!(OrdinalScale::new(&["a", "b", "a"]).is_err());
+        assert!(OrdinalScale::new(&[]).is_err());
+
+        let grades = OrdinalScale::new(&["C", "B", "A"]).unwrap();
+        let other = OrdinalScale::new(&["A", "B", "C"]).unwrap();
+        let a = grades.value("A").unwrap();
+        assert_eq!(a, OrdinalScale::new(&["C", "B", "A"]).unwrap().value("A").unwrap());
+        assert!(a.partial_cmp(&other.value("A").unwrap()).is_none());
+        assert!(a.distance(&other.value("A").unwrap()).is_nan());
+    }
+}
@@ -0,0 +1,324 @@
+use std::{
+    rc::Rc,
+    fmt::{ Display, Formatter, Result as FmtResult }
+};
+
+use polars::prelude::{ Series, NamedFrom, Result as PolarsResult };
+
+use crate::{
+    data::{ DataType, DataTypeValue, DataTypeValueStr },
+    numeric::Numeric
+};
+
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    pub decimal_separator: char,
+    // a space also accepts the no-break spaces used by spreadsheets
+    pub thousands_separator: Option<char>,
+    // compared case-insensitively
+    pub true_tokens: Vec<String>,
+    pub false_tokens: Vec<String>,
+    pub na_tokens: Vec<String>,
+    pub trim: bool,
+    // "12.5%" parses as 0.125 for float types
+    pub percent: bool,
+    // 0x, 0o and 0b prefixed integers
+    pub radix_prefixes: bool
+}
+
+fn tokens(tokens: &[&str]) -> Vec<String> { tokens.iter().map(|x| x.to_string()).collect() }
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            decimal_separator: '.',
+            thousands_separator: None,
+            true_tokens: tokens(&["true"]),
+            false_tokens: tokens(&["false"]),
+            na_tokens: Vec::new(),
+            trim: false,
+            percent: false,
+            radix_prefixes: false
+        }
+    }
+}
+
+impl ParseOptions {
+    // spreadsheet exports: "1,234.5", "yes", "N/A", "50%", "0x1F"
+    pub fn lenient() -> ParseOptions {
+        ParseOptions {
+            thousands_separator: Some(','),
+            true_tokens: tokens(&["true", "t", "yes", "y"]),
+            false_tokens: tokens(&["false", "f", "no", "n"]),
+            na_tokens: tokens(&["", "na", "n/a", "nan", "null", "none", "-"]),
+            trim: true,
+            percent: true,
+            radix_prefixes: true,
+            ..Default::default()
+        }
+    }
+
+    // continental european exports: "1 234,5", "12,5 %"
+    pub fn european() -> ParseOptions {
+        ParseOptions {
+            decimal_separator: ',',
+            thousands_separator: Some(' '),
+            ..ParseOptions::lenient()
+        }
+    }
+
+    fn matches(tokens: &[String], cell: &str) -> bool {
+        tokens.iter().any(|token| token.eq_ignore_ascii_case(cell))
+    }
+
+    pub fn is_na(&self, cell: &str) -> bool {
+        ParseOptions::matches(&self.na_tokens, self.prepare(cell))
+    }
+
+    fn prepare<'a>(&self, cell: &'a str) -> &'a str {
+        if self.trim { cell.trim() } else { cell }
+    }
+
+    fn is_thousands_separator(&self, c: char) -> bool {
+        match self.thousands_separator {
+            Some(' ') => matches!(c, ' ' | '\u{a0}' | '\u{202f}'),
+            Some(separator) => c == separator,
+            None => false
+        }
+    }
+
+    // sign, digits and an optional '.' fraction, separators removed,
+    // every group after a thousands separator has exactly three digits
+    fn normalize(&self, cell: &str) -> Option<String> {
+        let mut normalized = String::with_capacity(cell.len());
+        let unsigned = cell.trim_start_matches(['+', '-']);
+        normalized.push_str(&cell[..cell.len() - unsigned.len()]);
+
+        let integer_end = unsigned
+            .find(|c: char| !c.is_ascii_digit() && !self.is_thousands_separator(c))
+            .unwrap_or(unsigned.len());
+        let (integer, rest) = unsigned.split_at(integer_end);
+        let groups: Vec<&str> = integer.split(|c| self.is_thousands_separator(c)).collect();
+        if groups.len() > 1 {
+            if !(1..=3).contains(&groups[0].len()) { return None }
+            if groups[1..].iter().any(|group| group.len() != 3) { return None }
+        }
+        groups.iter().for_each(|group| normalized.push_str(group));
+
+        let mut seen_decimal = false;
+        for c in rest.chars() {
+            if c == self.decimal_separator && !seen_decimal {
+                seen_decimal = true;
+                normalized.push('.');
+            } else if self.is_thousands_separator(c) || c == '.' {
+                // separators are only allowed in the integer part
+                return None
+            } else {
+                normalized.push(c);
+            }
+        }
+        Some(normalized)
+    }
+
+    fn parse_integer(&self, cell: &str) -> Option<Numeric> {
+        let (negative, digits) = match cell.as_bytes().first()? {
+            b'-' => (true, &cell[1..]),
+            b'+' => (false, &cell[1..]),
+            _ => (false, cell)
+        };
+        let normalized;
+        let (radix, digits) = match digits.get(..2).map(|x| x.to_ascii_lowercase()) {
+            Some(prefix) if self.radix_prefixes && prefix == "0x" => (16, &digits[2..]),
+            Some(prefix) if self.radix_prefixes && prefix == "0o" => (8, &digits[2..]),
+            Some(prefix) if self.radix_prefixes && prefix == "0b" => (2, &digits[2..]),
+            _ => {
+                normalized = self.normalize(digits)?;
+                (10, normalized.as_str())
+            }
+        };
+        if digits.is_empty() || digits.starts_with(['+', '-']) { return None }
+        let magnitude = u128::from_str_radix(digits, radix).ok()?;
+        if negative {
+            if magnitude == 1 << 127 { return Some(Numeric::Signed(i128::MIN)) }
+            Some(Numeric::Signed(-i128::try_from(magnitude).ok()?))
+        } else {
+            Some(Numeric::Unsigned(magnitude))
+        }
+    }
+
+    // normalized number and the divisor of a percentage
+    fn parse_float(&self, cell: &str) -> Option<(String, f64)> {
+        let (cell, scale) = match cell.strip_suffix('%') {
+            Some(cell) if self.percent => (cell.trim_end(), 100.0),
+            _ => (cell, 1.0)
+        };
+        Some((self.normalize(cell)?, scale))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub cell: String,
+    pub to: DataType
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "cannot parse {:?} as {}", self.cell, self.to)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// Ok(None) for the NA tokens
+pub fn parse_cell(
+    cell: &str, data_type: DataType, options: &ParseOptions
+) -> Result<Option<DataTypeValue>, ParseError> {
+    if options.is_na(cell) { return Ok(None) }
+    let prepared = options.prepare(cell);
+    let value = match data_type {
+        DataType::Bool => {
+            if ParseOptions::matches(&options.true_tokens, prepared) {
+                Some(DataTypeValue::Bool(true))
+            } else if ParseOptions::matches(&options.false_tokens, prepared) {
+                Some(DataTypeValue::Bool(false))
+            } else {
+                None
+            }
+        }
+        DataType::F32 | DataType::F64 => {
+            options.parse_float(prepared).and_then(|(normalized, scale)| {
+                if data_type == DataType::F32 && scale == 1.0 {
+                    normalized.parse().ok().map(DataTypeValue::F32)
+                } else {
+                    let value = normalized.parse::<f64>().ok()? / scale;
+                    if data_type == DataType::F32 {
+                        Some(DataTypeValue::F32(value as f32))
+                    } else {
+                        Some(DataTypeValue::F64(value))
+                    }
+                }
+            })
+        }
+        data_type if data_type.is_integer() => {
+            options.parse_integer(prepared).and_then(|number| number.to_value(&data_type))
+        }
+        DataType::RcStr => Some(DataTypeValue::RcStr(Rc::from(prepared))),
+        DataType::String => Some(DataTypeValue::String(prepared.to_string())),
+        _ => DataTypeValueStr(prepared).data_type_value(data_type)
+    };
+    value.map(Some).ok_or_else(|| ParseError { cell: cell.to_string(), to: data_type })
+}
+
+fn parse_all<T>(
+    cells: &[Option<&str>],
+    data_type: DataType,
+    options: &ParseOptions,
+    into: fn(DataTypeValue) -> Option<T>
+) -> Option<Vec<Option<T>>> {
+    cells.iter().map(|cell| match cell {
+        Some(cell) => parse_cell(cell, data_type, options).ok().map(|v| v.and_then(into)),
+        None => Some(None)
+    }).collect()
+}
+
+// types a Utf8 series as Boolean, Int64, Float64 or Utf8, whichever parses every cell first,
+// a series without any value stays Utf8
+pub fn parse_series(series: &Series, options: &ParseOptions) -> PolarsResult<Series> {
+    let name = series.name();
+    let cells: Vec<Option<&str>> = series.utf8()?.into_iter().collect();
+    if cells.iter().flatten().all(|cell| options.is_na(cell)) {
+        return Ok(Series::new(name, vec![None::<&str>; cells.len()]))
+    }
+    if let Some(v) = parse_all(&cells, DataType::Bool, options, |v| v.into_bool().ok()) {
+        return Ok(Series::new(name, v))
+    }
+    if let Some(v) = parse_all(&cells, DataType::I64, options, |v| v.into_i64().ok()) {
+        return Ok(Series::new(name, v))
+    }
+    if let Some(v) = parse_all(&cells, DataType::F64, options, |v| v.into_f64().ok()) {
+        return Ok(Series::new(name, v))
+    }
+    let v: Vec<Option<&str>> = cells.into_iter()
+        .map(|cell| cell.filter(|cell| !options.is_na(cell)).map(|cell| options.prepare(cell)))
+        .collect();
+    Ok(Series::new(name, v))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ fs, env, process };
+
+    use pretty_assertions::assert_eq;
+
+    use polars::prelude::DataType as PolarsDataType;
+
+    use super::{ ParseOptions, parse_cell };
+    use crate::{
+        data::{ DataType, DataTypeValue, DataTypeValueStr },
+        polars::csv_to_dataframe_with
+    };
+
+    #[test]
+    fn locale_aware_cells() {
+        let options = ParseOptions::european();
+        let parse = |cell: &str, data_type| parse_cell(cell, data_type, &options);
+        assert_eq!(parse("1 234,5", DataType::F64), Ok(Some(DataTypeValue::F64(1234.5))));
+        assert_eq!(parse("1\u{a0}234", DataType::U16), Ok(Some(DataTypeValue::U16(1234))));
+        assert_eq!(parse("12,5 %", DataType::F64), Ok(Some(DataTypeValue::F64(0.125))));
+        assert_eq!(parse(" N/A ", DataType::I32), Ok(None));
+        assert!(parse("1,5", DataType::I32).is_err());
+        assert!(parse("1.5", DataType::F64).is_err());
+        assert!(parse("1 2", DataType::I64).is_err());
+        assert!(parse("1 2345", DataType::I64).is_err());
+        assert_eq!(parse("-12 345 678", DataType::I64), Ok(Some(DataTypeValue::I64(-12_345_678))));
+
+        let options = ParseOptions::lenient();
+        let parse = |cell: &str, data_type| {
+            DataTypeValueStr(cell).data_type_value_with(data_type, &options)
+        };
+        assert_eq!(parse("1,234.5", DataType::F32), Ok(Some(DataTypeValue::F32(1234.5))));
+        assert_eq!(parse("Y", DataType::Bool), Ok(Some(DataTypeValue::Bool(true))));
+        assert_eq!(parse("TRUE", DataType::Bool), Ok(Some(DataTypeValue::Bool(true))));
+        assert_eq!(parse("no", DataType::Bool), Ok(Some(DataTypeValue::Bool(false))));
+        assert_eq!(parse("0x1F", DataType::U8), Ok(Some(DataTypeValue::U8(31))));
+        assert_eq!(parse("-0b101", DataType::I8), Ok(Some(DataTypeValue::I8(-5))));
+        assert!(parse("0x100", DataType::U8).is_err());
+        assert!(parse(",1", DataType::I64).is_err());
+        assert!(parse("1,5", DataType::I64).is_err());
+        assert!(parse("3,14", DataType::F64).is_err());
+        assert!(parse("1234,567", DataType::I64).is_err());
+        assert!(parse("1,234,56", DataType::F64).is_err());
+        assert_eq!(parse("+1,234,567", DataType::I64), Ok(Some(DataTypeValue::I64(1_234_567))));
+
+        let strict = ParseOptions::default();
+        assert!(parse_cell("yes", DataType::Bool, &strict).is_err());
+        assert!(parse_cell("0x1F", DataType::U8, &strict).is_err());
+        assert!(parse_cell(" 1", DataType::U8, &strict).is_err());
+    }
+
+    #[test]
+    fn csv_with_options() {
+        let path = env::temp_dir().join(format!("bionet_common_parsing_{}.csv", process::id()));
+        fs::write(&path, "price;active;name;empty\n1 234,5;tak;a;NA\nNA;nie;b;\n7;tak;NA;NA\n")
+            .unwrap();
+        let options = ParseOptions {
+            true_tokens: vec!["tak".to_string()],
+            false_tokens: vec!["nie".to_string()],
+            ..ParseOptions::european()
+        };
+        let df = csv_to_dataframe_with(path.to_str().unwrap(), b';', &options).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(df.column("price").unwrap().dtype(), &PolarsDataType::Float64);
+        assert_eq!(df.column("active").unwrap().dtype(), &PolarsDataType::Boolean);
+        assert_eq!(df.column("name").unwrap().dtype(), &PolarsDataType::Utf8);
+        assert_eq!(df.column("empty").unwrap().dtype(), &PolarsDataType::Utf8);
+        assert_eq!(df.column("empty").unwrap().null_count(), 3);
+        let prices: Vec<Option<f64>> = df.column("price").unwrap().f64().unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(prices, vec![Some(1234.5), None, Some(7.0)]);
+        assert_eq!(df.column("name").unwrap().null_count(), 1);
+    }
+}
@@ -1,37 +1,274 @@
-use std::fs::File;
+use std::{
+    fs::File,
+    rc::Rc
+};
 
 use polars::prelude::*;
 
+use crate::{
+    data::{ self, DataTypeValue },
+    cast::{ self, CastMode, CastError, CastErrorKind },
+    temporal::{ Date, Datetime, Duration, TimeUnit },
+    column::{ Bitmap, Column },
+    interner::StringInterner,
+    parsing::{ self, ParseOptions },
+    geo::GeoPoint,
+    embedding::{ Embedding, VectorMetric, VectorError }
+};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DataVec {
-    BoolVec(Vec<bool>),
-    UInt8Vec(Vec<u8>),
-    UInt16Vec(Vec<u16>),
-    UInt32Vec(Vec<u32>),
-    UInt64Vec(Vec<u64>),
-    Int8Vec(Vec<i8>),
-    Int16Vec(Vec<i16>),
-    Int32Vec(Vec<i32>),
-    Int64Vec(Vec<i64>),
-    Float32Vec(Vec<f32>),
-    Float64Vec(Vec<f64>),
-    Utf8Vec(Vec<String>),
+    BoolVec(Column<bool>),
+    UInt8Vec(Column<u8>),
+    UInt16Vec(Column<u16>),
+    UInt32Vec(Column<u32>),
+    UInt64Vec(Column<u64>),
+    UInt128Vec(Column<u128>),
+    USizeVec(Column<usize>),
+    Int8Vec(Column<i8>),
+    Int16Vec(Column<i16>),
+    Int32Vec(Column<i32>),
+    Int64Vec(Column<i64>),
+    Int128Vec(Column<i128>),
+    ISizeVec(Column<isize>),
+    Float32Vec(Column<f32>),
+    Float64Vec(Column<f64>),
+    Utf8Vec(Column<String>),
+    RcStrVec(Column<Rc<str>>),
+    DateVec(Column<Date>),
+    // temporal columns carry their unit, every valid value is in it
+    DatetimeVec(Column<Datetime>, TimeUnit),
+    DurationVec(Column<Duration>, TimeUnit),
     Unknown
 }
 
-pub enum DataVecOption {
-    BoolVec(Vec<Option<bool>>),
-    UInt8Vec(Vec<Option<u8>>),
-    UInt16Vec(Vec<Option<u16>>),
-    UInt32Vec(Vec<Option<u32>>),
-    UInt64Vec(Vec<Option<u64>>),
-    Int8Vec(Vec<Option<i8>>),
-    Int16Vec(Vec<Option<i16>>),
-    Int32Vec(Vec<Option<i32>>),
-    Int64Vec(Vec<Option<i64>>),
-    Float32Vec(Vec<Option<f32>>),
-    Float64Vec(Vec<Option<f64>>),
-    Utf8Vec(Vec<Option<String>>),
-    Unknown
+macro_rules! impl_datavec_values {
+    ( $(
+        $data_type:pat => $variant:ident $([$unit:ident])?,
+        $t:ty, $value:ident, $into:ident, $as:ident
+    );* ) => {
+        impl DataVec {
+            pub fn len(&self) -> usize {
+                match self {
+                    $( DataVec::$variant(v, ..) => v.len(), ) *
+                    DataVec::Unknown => 0
+                }
+            }
+
+            pub fn null_count(&self) -> usize {
+                match self {
+                    $( DataVec::$variant(v, ..) => v.null_count(), ) *
+                    DataVec::Unknown => 0
+                }
+            }
+
+            pub fn validity(&self) -> Option<&Bitmap> {
+                match self {
+                    $( DataVec::$variant(v, ..) => v.validity(), ) *
+                    DataVec::Unknown => None
+                }
+            }
+
+            // None for nulls and out of range indices
+            pub fn get(&self, i: usize) -> Option<DataTypeValue> {
+                match self {
+                    $( DataVec::$variant(v, ..) => v.get(i).cloned().map(DataTypeValue::$value), ) *
+                    DataVec::Unknown => None
+                }
+            }
+
+            pub fn drop_nulls(&self) -> DataVec {
+                match self {
+                    $( DataVec::$variant(v $(, $unit)?) => {
+                        DataVec::$variant(v.drop_nulls() $(, *$unit)?)
+                    } ) *
+                    DataVec::Unknown => DataVec::Unknown
+                }
+            }
+
+            $( pub fn $as(&self) -> Option<&Column<$t>> {
+                match self {
+                    DataVec::$variant(v, ..) => Some(v),
+                    _ => None
+                }
+            }) *
+
+            // values failing the conversion into the column type become nulls
+            pub(crate) fn from_values(
+                values: Vec<Option<DataTypeValue>>, data_type: &data::DataType
+            ) -> Option<DataVec> {
+                match data_type {
+                    $( $data_type => Some(DataVec::$variant(
+                        values.into_iter()
+                            .map(|v| v.and_then(|x| conform(x, data_type)?.$into().ok()))
+                            .collect()
+                        $(, *$unit)?
+                    )), ) *
+                    _ => None
+                }
+            }
+        }
+    }
+}
+
+impl_datavec_values! {
+    data::DataType::Bool => BoolVec, bool, Bool, into_bool, as_bool;
+    data::DataType::U8 => UInt8Vec, u8, U8, into_u8, as_u8;
+    data::DataType::U16 => UInt16Vec, u16, U16, into_u16, as_u16;
+    data::DataType::U32 => UInt32Vec, u32, U32, into_u32, as_u32;
+    data::DataType::U64 => UInt64Vec, u64, U64, into_u64, as_u64;
+    data::DataType::U128 => UInt128Vec, u128, U128, into_u128, as_u128;
+    data::DataType::USize => USizeVec, usize, USize, into_u_size, as_usize;
+    data::DataType::I8 => Int8Vec, i8, I8, into_i8, as_i8;
+    data::DataType::I16 => Int16Vec, i16, I16, into_i16, as_i16;
+    data::DataType::I32 => Int32Vec, i32, I32, into_i32, as_i32;
+    data::DataType::I64 => Int64Vec, i64, I64, into_i64, as_i64;
+    data::DataType::I128 => Int128Vec, i128, I128, into_i128, as_i128;
+    data::DataType::ISize => ISizeVec, isize, ISize, into_i_size, as_isize;
+    data::DataType::F32 => Float32Vec, f32, F32, into_f32, as_f32;
+    data::DataType::F64 => Float64Vec, f64, F64, into_f64, as_f64;
+    data::DataType::String => Utf8Vec, String, String, into_string, as_string;
+    data::DataType::RcStr => RcStrVec, Rc<str>, RcStr, into_rc_str, as_rc_str;
+    data::DataType::Date => DateVec, Date, Date, into_date, as_date;
+    data::DataType::Datetime(unit) => DatetimeVec [unit],
+        Datetime, Datetime, into_datetime, as_datetime;
+    data::DataType::Duration(unit) => DurationVec [unit],
+        Duration, Duration, into_duration, as_duration
+}
+
+// temporal columns have no From impls, their unit is not implied by the value type
+macro_rules! impl_datavec_from {
+    ( $($variant:ident, $t:ty);* ) => {
+        $( impl From<Column<$t>> for DataVec {
+            fn from(column: Column<$t>) -> DataVec { DataVec::$variant(column) }
+        }
+
+        impl From<Vec<$t>> for DataVec {
+            fn from(values: Vec<$t>) -> DataVec { DataVec::$variant(values.into()) }
+        }
+
+        impl From<Vec<Option<$t>>> for DataVec {
+            fn from(values: Vec<Option<$t>>) -> DataVec { DataVec::$variant(values.into()) }
+        }
+
+        impl FromIterator<$t> for DataVec {
+            fn from_iter<I: IntoIterator<Item = $t>>(iter: I) -> DataVec {
+                DataVec::$variant(iter.into_iter().collect())
+            }
+        }
+
+        impl FromIterator<Option<$t>> for DataVec {
+            fn from_iter<I: IntoIterator<Item = Option<$t>>>(iter: I) -> DataVec {
+                DataVec::$variant(iter.into_iter().collect())
+            }
+        }) *
+    }
+}
+
+impl_datavec_from! {
+    BoolVec, bool; UInt8Vec, u8; UInt16Vec, u16; UInt32Vec, u32; UInt64Vec, u64;
+    UInt128Vec, u128; USizeVec, usize; Int8Vec, i8; Int16Vec, i16; Int32Vec, i32;
+    Int64Vec, i64; Int128Vec, i128; ISizeVec, isize; Float32Vec, f32; Float64Vec, f64;
+    Utf8Vec, String; RcStrVec, Rc<str>; DateVec, Date
+}
+
+// temporal values are rescaled exactly into the unit of their column
+fn conform(value: DataTypeValue, data_type: &data::DataType) -> Option<DataTypeValue> {
+    match (&value, data_type) {
+        (DataTypeValue::Datetime(_), data::DataType::Datetime(_))
+        | (DataTypeValue::Duration(_), data::DataType::Duration(_)) => {
+            cast::cast_value(&value, data_type, CastMode::Checked).ok()
+        }
+        _ => Some(value)
+    }
+}
+
+impl DataVec {
+    pub fn data_type(&self) -> data::DataType {
+        match self {
+            DataVec::BoolVec(_) => data::DataType::Bool,
+            DataVec::UInt8Vec(_) => data::DataType::U8,
+            DataVec::UInt16Vec(_) => data::DataType::U16,
+            DataVec::UInt32Vec(_) => data::DataType::U32,
+            DataVec::UInt64Vec(_) => data::DataType::U64,
+            DataVec::UInt128Vec(_) => data::DataType::U128,
+            DataVec::USizeVec(_) => data::DataType::USize,
+            DataVec::Int8Vec(_) => data::DataType::I8,
+            DataVec::Int16Vec(_) => data::DataType::I16,
+            DataVec::Int32Vec(_) => data::DataType::I32,
+            DataVec::Int64Vec(_) => data::DataType::I64,
+            DataVec::Int128Vec(_) => data::DataType::I128,
+            DataVec::ISizeVec(_) => data::DataType::ISize,
+            DataVec::Float32Vec(_) => data::DataType::F32,
+            DataVec::Float64Vec(_) => data::DataType::F64,
+            DataVec::Utf8Vec(_) => data::DataType::String,
+            DataVec::RcStrVec(_) => data::DataType::RcStr,
+            DataVec::DateVec(_) => data::DataType::Date,
+            DataVec::DatetimeVec(_, unit) => data::DataType::Datetime(*unit),
+            DataVec::DurationVec(_, unit) => data::DataType::Duration(*unit),
+            DataVec::Unknown => data::DataType::Unknown
+        }
+    }
+
+    // values not exactly representable in the unit become nulls
+    pub fn from_datetimes<I: IntoIterator<Item = Option<Datetime>>>(
+        values: I, unit: TimeUnit
+    ) -> DataVec {
+        let data_type = data::DataType::Datetime(unit);
+        DataVec::DatetimeVec(
+            values.into_iter()
+                .map(|v| v.and_then(|x| {
+                    conform(DataTypeValue::Datetime(x), &data_type)?.into_datetime().ok()
+                }))
+                .collect(),
+            unit
+        )
+    }
+
+    pub fn from_durations<I: IntoIterator<Item = Option<Duration>>>(
+        values: I, unit: TimeUnit
+    ) -> DataVec {
+        let data_type = data::DataType::Duration(unit);
+        DataVec::DurationVec(
+            values.into_iter()
+                .map(|v| v.and_then(|x| {
+                    conform(DataTypeValue::Duration(x), &data_type)?.into_duration().ok()
+                }))
+                .collect(),
+            unit
+        )
+    }
+
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    pub fn iter(&self) -> impl Iterator<Item = Option<DataTypeValue>> + '_ {
+        (0..self.len()).map(|i| self.get(i))
+    }
+
+    // skips the nulls
+    pub fn iter_valid(&self) -> impl Iterator<Item = DataTypeValue> + '_ {
+        self.iter().flatten()
+    }
+}
+
+fn unsupported_column(data_type: &data::DataType) -> CastError {
+    CastError {
+        value: "column".to_string(),
+        to: data_type.to_string(),
+        kind: CastErrorKind::Unsupported
+    }
+}
+
+impl DataVec {
+    pub fn cast(
+        &self, data_type: data::DataType, mode: CastMode
+    ) -> std::result::Result<DataVec, CastError> {
+        let values = self.iter()
+            .map(|v| v.as_ref().map(|x| cast::cast_value(x, &data_type, mode)).transpose())
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        DataVec::from_values(values, &data_type).ok_or_else(|| unsupported_column(&data_type))
+    }
 }
 
 pub fn csv_to_dataframe(filename: &str) -> Result<DataFrame> {
@@ -39,63 +276,114 @@ pub fn csv_to_dataframe(filename: &str) -> Result<DataFrame> {
     CsvReader::new(file).infer_schema(None).has_header(true).finish()
 }
 
+// reads every column as text and types it with the parse options
+pub fn csv_to_dataframe_with(
+    filename: &str, delimiter: u8, options: &ParseOptions
+) -> Result<DataFrame> {
+    let file = File::open(filename)?;
+    let raw = CsvReader::new(file)
+        .infer_schema(Some(0))
+        .with_delimiter(delimiter)
+        .has_header(true)
+        .finish()?;
+    let columns = raw.get_columns().iter()
+        .map(|series| parsing::parse_series(series, options))
+        .collect::<Result<Vec<_>>>()?;
+    DataFrame::new(columns)
+}
+
 pub fn series_to_datavec_skipna(series: &Series) -> Result<DataVec> {
+    Ok(series_to_datavec(series)?.drop_nulls())
+}
+
+pub fn series_to_datavec(series: &Series) -> Result<DataVec> {
     match series.dtype() {
-        DataType::UInt8 => Ok(DataVec::UInt8Vec(
-            series.u8()?.into_iter().filter(|x| x.is_some()).map(|x| x.unwrap()).collect()
-        )),
-        DataType::UInt16 => Ok(DataVec::UInt16Vec(
-            series.u16()?.into_iter().filter(|x| x.is_some()).map(|x| x.unwrap()).collect()
-        )),
-        DataType::UInt32 => Ok(DataVec::UInt32Vec(
-            series.u32()?.into_iter().filter(|x| x.is_some()).map(|x| x.unwrap()).collect()
-        )),
-        DataType::UInt64 => Ok(DataVec::UInt64Vec(
-            series.u64()?.into_iter().filter(|x| x.is_some()).map(|x| x.unwrap()).collect()
-        )),
-        DataType::Int8 => Ok(DataVec::Int8Vec(
-            series.i8()?.into_iter().filter(|x| x.is_some()).map(|x| x.unwrap()).collect()
-        )),
-        DataType::Int16 => Ok(DataVec::Int16Vec(
-            series.i16()?.into_iter().filter(|x| x.is_some()).map(|x| x.unwrap()).collect()
-        )),
-        DataType::Int32 => Ok(DataVec::Int32Vec(
-            series.i32()?.into_iter().filter(|x| x.is_some()).map(|x| x.unwrap()).collect()
-        )),
-        DataType::Int64 => Ok(DataVec::Int64Vec(
-            series.i64()?.into_iter().filter(|x| x.is_some()).map(|x| x.unwrap()).collect()
-        )),
-        DataType::Float32 => Ok(DataVec::Float32Vec(
-            series.f32()?.into_iter().filter(|x| x.is_some()).map(|x| x.unwrap()).collect()
+        DataType::UInt8 => Ok(DataVec::UInt8Vec(series.u8()?.into_iter().collect())),
+        DataType::UInt16 => Ok(DataVec::UInt16Vec(series.u16()?.into_iter().collect())),
+        DataType::UInt32 => Ok(DataVec::UInt32Vec(series.u32()?.into_iter().collect())),
+        DataType::UInt64 => Ok(DataVec::UInt64Vec(series.u64()?.into_iter().collect())),
+        DataType::Int8 => Ok(DataVec::Int8Vec(series.i8()?.into_iter().collect())),
+        DataType::Int16 => Ok(DataVec::Int16Vec(series.i16()?.into_iter().collect())),
+        DataType::Int32 => Ok(DataVec::Int32Vec(series.i32()?.into_iter().collect())),
+        DataType::Int64 => Ok(DataVec::Int64Vec(series.i64()?.into_iter().collect())),
+        DataType::Float32 => Ok(DataVec::Float32Vec(series.f32()?.into_iter().collect())),
+        DataType::Float64 => Ok(DataVec::Float64Vec(series.f64()?.into_iter().collect())),
+        DataType::Utf8 => Ok(DataVec::Utf8Vec(
+            series.utf8()?.into_iter().map(|x| x.map(|y| y.to_string())).collect()
         )),
-        DataType::Float64 => Ok(DataVec::Float64Vec(
-            series.f64()?.into_iter().filter(|x| x.is_some()).map(|x| x.unwrap()).collect()
+        DataType::Date => Ok(DataVec::DateVec(
+            series.date()?.into_iter().map(|x| x.map(Date)).collect()
         )),
-        DataType::Utf8 => Ok(DataVec::Utf8Vec(
-            series.utf8()?.into_iter()
-                .filter(|x| x.is_some()).map(|x| x.unwrap().to_string()).collect()
+        DataType::Datetime(unit, timezone) => {
+            let timezone: Option<Rc<str>> = timezone.as_deref().map(Rc::from);
+            Ok(DataVec::DatetimeVec(
+                series.datetime()?.into_iter()
+                    .map(|x| x.map(|y| Datetime::new(y, (*unit).into(), timezone.clone())))
+                    .collect(),
+                (*unit).into()
+            ))
+        }
+        DataType::Duration(unit) => Ok(DataVec::DurationVec(
+            series.duration()?.into_iter()
+                .map(|x| x.map(|y| Duration::new(y, (*unit).into()))).collect(),
+            (*unit).into()
         )),
         _ => Ok(DataVec::Unknown)
     }
 }
 
-pub fn series_to_datavec(series: &Series) -> Result<DataVecOption> {
+// pairs two numeric columns of one length into points,
+// None for nulls and out of range coordinates
+pub fn series_to_geopoints(lat: &Series, lon: &Series) -> Result<Vec<Option<GeoPoint>>> {
+    if lat.len() != lon.len() {
+        return Err(PolarsError::ShapeMisMatch(
+            format!("{} latitudes and {} longitudes", lat.len(), lon.len()).into()
+        ))
+    }
+    let (lat, lon) = (lat.cast(&DataType::Float64)?, lon.cast(&DataType::Float64)?);
+    Ok(
+        lat.f64()?.into_iter().zip(lon.f64()?)
+            .map(|(lat, lon)| GeoPoint::new(lat?, lon?))
+            .collect()
+    )
+}
+
+// List(Float32) into embeddings of one dimension, None for null lists,
+// null elements are rejected as not finite
+pub fn series_to_embeddings(
+    series: &Series, metric: VectorMetric
+) -> Result<Vec<Option<Embedding>>> {
+    let mut dim = None;
+    series.list()?.into_iter().map(|item| {
+        let item = match item {
+            Some(item) => item.cast(&DataType::Float32)?,
+            None => return Ok(None)
+        };
+        let values = item.f32()?.into_iter().map(|x| x.unwrap_or(f32::NAN)).collect();
+        let embedding = Embedding::new(values, metric)
+            .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+        match dim {
+            Some(dim) if dim != embedding.dim() => {
+                return Err(PolarsError::ShapeMisMatch(
+                    VectorError::DimensionMismatch { expected: dim, found: embedding.dim() }
+                        .to_string()
+                        .into()
+                ))
+            }
+            _ => dim = Some(embedding.dim())
+        }
+        Ok(Some(embedding))
+    }).collect()
+}
+
+// as series_to_datavec, but Utf8 columns become RcStrVec sharing equal strings
+pub fn series_to_datavec_interned(
+    series: &Series, interner: &mut StringInterner
+) -> Result<DataVec> {
     match series.dtype() {
-        DataType::UInt8 => Ok(DataVecOption::UInt8Vec(series.u8()?.into_iter().collect())),
-        DataType::UInt16 => Ok(DataVecOption::UInt16Vec(series.u16()?.into_iter().collect())),
-        DataType::UInt32 => Ok(DataVecOption::UInt32Vec(series.u32()?.into_iter().collect())),
-        DataType::UInt64 => Ok(DataVecOption::UInt64Vec(series.u64()?.into_iter().collect())),
-        DataType::Int8 => Ok(DataVecOption::Int8Vec(series.i8()?.into_iter().collect())),
-        DataType::Int16 => Ok(DataVecOption::Int16Vec(series.i16()?.into_iter().collect())),
-        DataType::Int32 => Ok(DataVecOption::Int32Vec(series.i32()?.into_iter().collect())),
-        DataType::Int64 => Ok(DataVecOption::Int64Vec(series.i64()?.into_iter().collect())),
-        DataType::Float32 => Ok(DataVecOption::Float32Vec(series.f32()?.into_iter().collect())),
-        DataType::Float64 => Ok(DataVecOption::Float64Vec(series.f64()?.into_iter().collect())),
-        DataType::Utf8 => Ok(DataVecOption::Utf8Vec(
-            series.utf8()?.into_iter()
-                .map(|x| match x { Some(y) => Some(y.to_string()), None => None })
-                .collect()
+        DataType::Utf8 => Ok(DataVec::RcStrVec(
+            series.utf8()?.into_iter().map(|x| x.map(|y| interner.intern(y))).collect()
         )),
-        _ => Ok(DataVecOption::Unknown)
+        _ => series_to_datavec(series)
     }
 }
\ No newline at end of file
@@ -9,13 +9,22 @@ use std::{
     mem
 };
 
-use num_traits::ToPrimitive;
-
 use dyn_clone::DynClone;
 
+pub use bionet_common_derive::SensorData;
+
 use crate::{
     data::{ DataCategory, DataType, DataTypeValue, DataDeductor, UnknownDataTypeMarker },
-    neuron::{ Neuron, NeuronID }
+    neuron::{ Neuron, NeuronID },
+    distances::Distance,
+    metric::{ Metric, SensorDistance },
+    temporal::{ Date, Datetime, Duration },
+    ordinal::Ordinal,
+    geo::GeoPoint,
+    embedding::Embedding,
+    taxonomy::TaxonomyNode,
+    interval::Interval,
+    text::Text
 };
 
 pub trait AnyCast {
@@ -35,7 +44,7 @@ pub trait SensorData: AnyCast + Display + DynClone + 'static {
 
 dyn_clone::clone_trait_object!(SensorData);
 
-macro_rules! impl_sensor_data_numeric {
+macro_rules! impl_sensor_data_distance {
     ( $($t:ty),* ) => {
         $( impl SensorData for $t {
             fn equals(&self, rhs: &dyn SensorData) -> bool {
@@ -47,13 +56,7 @@ macro_rules! impl_sensor_data_numeric {
             }
 
             fn distance(&self, rhs: &dyn SensorData) -> f64 {
-                let rhs = *rhs.any().downcast_ref::<$t>().unwrap();
-                unsafe { 
-                    (
-                        Self::to_f64(self).unwrap_unchecked() - 
-                        Self::to_f64(&rhs).unwrap_unchecked()
-                    ).abs()
-                }
+                Distance::distance(self, rhs.any().downcast_ref::<$t>().unwrap())
             }
         }) *
     }
@@ -77,10 +80,11 @@ macro_rules! impl_sensor_data_categoric {
     }
 }
 
-impl_sensor_data_numeric! { 
+impl_sensor_data_distance! {
     i8, i16, i32, i64, i128, isize,
     u8, u16, u32, u64, u128, usize,
-    f32, f64
+    f32, f64,
+    Date, Datetime, Duration, Ordinal, GeoPoint, Embedding, TaxonomyNode, Interval, Text
 }
 
 impl_sensor_data_categoric! {
@@ -151,6 +155,12 @@ pub trait Sensor<D: SensorData>: Any + Display {
     ) -> Result<(), String>;
 
     fn deactivate_sensor(&mut self);
+
+    // metric attached at construction, kept in a field such as Box<dyn Metric<D>>
+    // by implementors that override it, SensorData::distance otherwise
+    fn metric(&self) -> &dyn Metric<D> { &SensorDistance }
+
+    fn distance(&self, lhs: &D, rhs: &D) -> f64 { self.metric().distance(lhs, rhs) }
 }
 
 pub trait SensorDynamicDowncast<D: SensorData> {
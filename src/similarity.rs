@@ -0,0 +1,206 @@
+use crate::{
+    data::DataTypeValue,
+    numeric::Numeric
+};
+
+// maps a distance scaled by the bandwidth into [0, 1], 1 at distance 0
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Kernel {
+    // exp(-u^2 / 2)
+    #[default]
+    Gaussian,
+    // 1 - u, 0 beyond the bandwidth
+    Triangular,
+    // 1 - u^2, 0 beyond the bandwidth
+    Epanechnikov,
+    // 1 / (1 + u)
+    InverseDistance
+}
+
+impl Kernel {
+    // NaN and negative distances give 0
+    pub fn apply(&self, u: f64) -> f64 {
+        if u.is_nan() || u < 0.0 { return 0.0 }
+        match self {
+            Kernel::Gaussian => (-u * u / 2.0).exp(),
+            Kernel::Triangular => (1.0 - u).max(0.0),
+            Kernel::Epanechnikov => (1.0 - u * u).max(0.0),
+            Kernel::InverseDistance => 1.0 / (1.0 + u)
+        }
+    }
+}
+
+// observed [min, max] of a sensor, kept exact for wide integers
+#[derive(Debug, Copy, Clone)]
+pub struct ValueRange {
+    min: Numeric,
+    max: Numeric
+}
+
+impl ValueRange {
+    // None for non-numeric values, NaN or min > max
+    pub fn new(min: &DataTypeValue, max: &DataTypeValue) -> Option<ValueRange> {
+        let (min, max) = (min.to_numeric()?, max.to_numeric()?);
+        if min.is_nan() || max.is_nan() || min > max { return None }
+        Some(ValueRange { min, max })
+    }
+
+    // None if no value is numeric
+    pub fn from_values<'a, I>(values: I) -> Option<ValueRange>
+    where I: IntoIterator<Item = &'a DataTypeValue> {
+        let mut range: Option<ValueRange> = None;
+        for value in values {
+            match &mut range {
+                Some(range) => { range.observe(value); }
+                None => range = ValueRange::new(value, value)
+            }
+        }
+        range
+    }
+
+    // widens the range, false for values that are not numeric
+    pub fn observe(&mut self, value: &DataTypeValue) -> bool {
+        match value.to_numeric() {
+            Some(number) if !number.is_nan() => {
+                if number < self.min { self.min = number }
+                if number > self.max { self.max = number }
+                true
+            }
+            _ => false
+        }
+    }
+
+    pub fn min(&self) -> f64 { self.min.to_f64() }
+
+    pub fn max(&self) -> f64 { self.max.to_f64() }
+
+    pub fn width(&self) -> f64 { self.min.distance(&self.max) }
+
+    // distance as a fraction of the width clamped to [0, 1],
+    // a zero-width range gives 0 for equal values and 1 otherwise, NaN for non-numeric values
+    pub fn normalized_distance(&self, lhs: &DataTypeValue, rhs: &DataTypeValue) -> f64 {
+        let (lhs, rhs) = match (lhs.to_numeric(), rhs.to_numeric()) {
+            (Some(lhs), Some(rhs)) => (lhs, rhs),
+            _ => return f64::NAN
+        };
+        let distance = lhs.distance(&rhs);
+        let width = self.width();
+        if width == 0.0 {
+            if distance == 0.0 { 0.0 } else { 1.0 }
+        } else {
+            (distance / width).min(1.0)
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Similarity {
+    kernel: Kernel,
+    bandwidth: f64,
+    range: Option<ValueRange>
+}
+
+impl Default for Similarity {
+    fn default() -> Similarity {
+        Similarity { kernel: Kernel::Gaussian, bandwidth: 1.0, range: None }
+    }
+}
+
+impl Similarity {
+    // None unless the bandwidth is positive and finite
+    pub fn new(kernel: Kernel, bandwidth: f64) -> Option<Similarity> {
+        if bandwidth > 0.0 && bandwidth.is_finite() {
+            Some(Similarity { kernel, bandwidth, range: None })
+        } else {
+            None
+        }
+    }
+
+    // distances between values are then taken as fractions of the range,
+    // so the bandwidth is a fraction of the range as well
+    pub fn with_range(self, range: ValueRange) -> Similarity {
+        Similarity { range: Some(range), ..self }
+    }
+
+    pub fn kernel(&self) -> Kernel { self.kernel }
+
+    pub fn bandwidth(&self) -> f64 { self.bandwidth }
+
+    pub fn range(&self) -> Option<&ValueRange> { self.range.as_ref() }
+
+    // for any already computed distance
+    pub fn similarity(&self, distance: f64) -> f64 {
+        self.kernel.apply(distance / self.bandwidth)
+    }
+
+    // similarity of two numeric values of any variant, 0 for non-numeric values
+    pub fn between(&self, lhs: &DataTypeValue, rhs: &DataTypeValue) -> f64 {
+        let distance = match &self.range {
+            Some(range) => range.normalized_distance(lhs, rhs),
+            None => match (lhs.to_numeric(), rhs.to_numeric()) {
+                (Some(lhs), Some(rhs)) => lhs.distance(&rhs),
+                _ => f64::NAN
+            }
+        };
+        self.similarity(distance)
+    }
+
+    // activation signal for Sensor::activate
+    pub fn signal(&self, lhs: &DataTypeValue, rhs: &DataTypeValue) -> f32 {
+        self.between(lhs, rhs) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{ Kernel, ValueRange, Similarity };
+    use crate::data::DataTypeValue;
+
+    #[test]
+    fn kernels() {
+        assert_eq!(Kernel::Gaussian.apply(0.0), 1.0);
+        assert_eq!(Kernel::Gaussian.apply(2.0), (-2f64).exp());
+        assert_eq!(Kernel::Triangular.apply(0.25), 0.75);
+        assert_eq!(Kernel::Triangular.apply(3.0), 0.0);
+        assert_eq!(Kernel::Epanechnikov.apply(0.5), 0.75);
+        assert_eq!(Kernel::InverseDistance.apply(1.0), 0.5);
+        assert_eq!(Kernel::InverseDistance.apply(f64::NAN), 0.0);
+
+        let similarity = Similarity::new(Kernel::Triangular, 4.0).unwrap();
+        assert_eq!(similarity.similarity(1.0), 0.75);
+        assert!(Similarity::new(Kernel::Gaussian, 0.0).is_none());
+        assert!(Similarity::new(Kernel::Gaussian, f64::INFINITY).is_none());
+    }
+
+    #[test]
+    fn range_normalized_signals() {
+        let values = [
+            DataTypeValue::U8(10), DataTypeValue::I64(-10), DataTypeValue::F32(30.0),
+            DataTypeValue::String("x".to_string())
+        ];
+        let mut range = ValueRange::from_values(&values).unwrap();
+        assert_eq!((range.min(), range.max(), range.width()), (-10.0, 30.0, 40.0));
+        assert!(range.observe(&DataTypeValue::U128(50)));
+        assert!(!range.observe(&DataTypeValue::Bool(true)));
+        assert_eq!(range.width(), 60.0);
+
+        let similarity = Similarity::new(Kernel::Triangular, 0.5).unwrap().with_range(range);
+        assert_eq!(similarity.between(&DataTypeValue::I8(0), &DataTypeValue::U16(15)), 0.5);
+        assert_eq!(similarity.signal(&DataTypeValue::ISize(-10), &DataTypeValue::F64(50.0)), 0.0);
+        assert_eq!(similarity.between(&DataTypeValue::USize(3), &DataTypeValue::U64(3)), 1.0);
+        assert_eq!(similarity.between(&DataTypeValue::I32(3), &DataTypeValue::Bool(true)), 0.0);
+
+        let wide = ValueRange::new(&DataTypeValue::I128(i128::MIN), &DataTypeValue::U128(u128::MAX))
+            .unwrap();
+        assert!(wide.width() > 5e38);
+        let point = ValueRange::new(&DataTypeValue::I16(7), &DataTypeValue::I16(7)).unwrap();
+        assert_eq!(point.normalized_distance(&DataTypeValue::I16(7), &DataTypeValue::U8(8)), 1.0);
+        assert!(ValueRange::new(&DataTypeValue::U8(2), &DataTypeValue::U8(1)).is_none());
+
+        let unscaled = Similarity::default();
+        assert_eq!(unscaled.between(&DataTypeValue::F32(1.0), &DataTypeValue::I8(1)), 1.0);
+    }
+}
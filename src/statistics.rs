@@ -0,0 +1,265 @@
+use polars::prelude::{ Series, Result as PolarsResult };
+
+use crate::{
+    data::{ DataType, DataTypeValue, DataCategory },
+    temporal::{ Date, Datetime, Duration },
+    ordered::OrderedDataTypeValue,
+    polars::{ DataVec, series_to_datavec }
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Statistic {
+    Count,
+    NullCount,
+    DistinctCount,
+    Min,
+    Max,
+    Mean,
+    Std,
+    Quantiles,
+    Mode,
+    TopK
+}
+
+impl Statistic {
+    pub fn meaningful_for(category: DataCategory) -> &'static [Statistic] {
+        match category {
+            DataCategory::Numerical => &[
+                Statistic::Count, Statistic::NullCount, Statistic::DistinctCount,
+                Statistic::Min, Statistic::Max, Statistic::Mean, Statistic::Std,
+                Statistic::Quantiles, Statistic::Mode, Statistic::TopK
+            ],
+            DataCategory::Ordinal => &[
+                Statistic::Count, Statistic::NullCount, Statistic::DistinctCount,
+                Statistic::Min, Statistic::Max, Statistic::Quantiles, Statistic::Mode,
+                Statistic::TopK
+            ],
+            DataCategory::Categorical => &[
+                Statistic::Count, Statistic::NullCount, Statistic::DistinctCount,
+                Statistic::Mode, Statistic::TopK
+            ]
+        }
+    }
+
+    pub fn is_meaningful_for(&self, category: DataCategory) -> bool {
+        Statistic::meaningful_for(category).contains(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StatisticsOptions {
+    // probabilities in [0, 1], answered with the nearest-rank value
+    pub quantiles: Vec<f64>,
+    pub top_k: usize
+}
+
+impl Default for StatisticsOptions {
+    fn default() -> StatisticsOptions {
+        StatisticsOptions { quantiles: vec![0.25, 0.5, 0.75], top_k: 5 }
+    }
+}
+
+// statistics not meaningful for the category are left as None or empty,
+// mean is F64 for numbers and keeps the type of temporal columns,
+// std is in the column units (days for dates)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStatistics {
+    pub data_type: DataType,
+    pub category: DataCategory,
+    pub count: usize,
+    pub null_count: usize,
+    pub distinct_count: usize,
+    pub min: Option<DataTypeValue>,
+    pub max: Option<DataTypeValue>,
+    pub mean: Option<DataTypeValue>,
+    pub std: Option<f64>,
+    pub quantiles: Vec<(f64, DataTypeValue)>,
+    pub mode: Option<DataTypeValue>,
+    // most frequent values, ties in ascending value order
+    pub top_k: Vec<(DataTypeValue, usize)>
+}
+
+impl ColumnStatistics {
+    pub fn meaningful(&self) -> &'static [Statistic] { Statistic::meaningful_for(self.category) }
+}
+
+// value in the units of the column type, None for non-numeric values
+fn to_units(value: &DataTypeValue, data_type: &DataType) -> Option<f64> {
+    match (value, data_type) {
+        (DataTypeValue::Date(v), _) => Some(v.0 as f64),
+        (DataTypeValue::Datetime(v), DataType::Datetime(unit)) => {
+            Some(v.nanoseconds() as f64 / unit.nanoseconds() as f64)
+        }
+        (DataTypeValue::Duration(v), DataType::Duration(unit)) => {
+            Some(v.nanoseconds() as f64 / unit.nanoseconds() as f64)
+        }
+        _ => value.to_numeric().map(|x| x.to_f64())
+    }
+}
+
+fn from_units(value: f64, data_type: &DataType, first: &DataTypeValue) -> DataTypeValue {
+    match (data_type, first) {
+        (DataType::Date, _) => DataTypeValue::Date(Date(value.round() as i32)),
+        (DataType::Datetime(unit), DataTypeValue::Datetime(first)) => DataTypeValue::Datetime(
+            Datetime::new(value.round() as i64, *unit, first.timezone.clone())
+        ),
+        (DataType::Duration(unit), _) => {
+            DataTypeValue::Duration(Duration::new(value.round() as i64, *unit))
+        }
+        _ => DataTypeValue::F64(value)
+    }
+}
+
+pub fn describe(column: &DataVec) -> ColumnStatistics {
+    describe_with(column, &StatisticsOptions::default())
+}
+
+pub fn describe_with(column: &DataVec, options: &StatisticsOptions) -> ColumnStatistics {
+    describe_values(column.iter(), column.data_type(), options)
+}
+
+// for value lists without a DataVec counterpart, such as ordinals
+pub fn describe_values<I>(
+    values: I, data_type: DataType, options: &StatisticsOptions
+) -> ColumnStatistics
+where I: IntoIterator<Item = Option<DataTypeValue>> {
+    let category = data_type.data_category();
+    let meaningful = |statistic: Statistic| statistic.is_meaningful_for(category);
+
+    let mut null_count = 0;
+    let mut sorted: Vec<OrderedDataTypeValue> = values.into_iter()
+        .filter_map(|x| {
+            if x.is_none() { null_count += 1 }
+            x.map(OrderedDataTypeValue::new)
+        })
+        .collect();
+    sorted.sort();
+
+    let mut frequencies: Vec<(&OrderedDataTypeValue, usize)> = Vec::new();
+    for value in sorted.iter() {
+        match frequencies.last_mut() {
+            Some((last, count)) if *last == value => *count += 1,
+            _ => frequencies.push((value, 1))
+        }
+    }
+    let mut by_count = frequencies.clone();
+    by_count.sort_by(|lhs, rhs| rhs.1.cmp(&lhs.1).then(lhs.0.cmp(rhs.0)));
+
+    let units: Option<Vec<f64>> = if meaningful(Statistic::Mean) && !sorted.is_empty() {
+        sorted.iter().map(|x| to_units(x.value(), &data_type)).collect()
+    } else {
+        None
+    };
+    let mean = units.as_ref().map(|x| x.iter().sum::<f64>() / x.len() as f64);
+    let std = match (&units, mean) {
+        (Some(units), Some(mean)) if units.len() > 1 => Some(
+            (units.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (units.len() - 1) as f64)
+                .sqrt()
+        ),
+        _ => None
+    };
+
+    let ordered = meaningful(Statistic::Min);
+    let quantiles = if meaningful(Statistic::Quantiles) && !sorted.is_empty() {
+        options.quantiles.iter().map(|q| {
+            let rank = (q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64).round() as usize;
+            (*q, sorted[rank].value().clone())
+        }).collect()
+    } else {
+        Vec::new()
+    };
+
+    ColumnStatistics {
+        data_type,
+        category,
+        count: sorted.len(),
+        null_count,
+        distinct_count: frequencies.len(),
+        min: sorted.first().filter(|_| ordered).map(|x| x.value().clone()),
+        max: sorted.last().filter(|_| ordered).map(|x| x.value().clone()),
+        mean: mean.map(|x| from_units(x, &data_type, sorted[0].value())),
+        std: if meaningful(Statistic::Std) { std } else { None },
+        quantiles,
+        mode: by_count.first().map(|(value, _)| value.value().clone()),
+        top_k: by_count.iter()
+            .take(options.top_k)
+            .map(|(value, count)| (value.value().clone(), *count))
+            .collect()
+    }
+}
+
+// Unknown statistics for unsupported series types
+pub fn describe_series(
+    series: &Series, options: &StatisticsOptions
+) -> PolarsResult<ColumnStatistics> {
+    Ok(describe_with(&series_to_datavec(series)?, options))
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::{ Series, NamedFrom };
+
+    use pretty_assertions::assert_eq;
+
+    use super::{ describe, describe_values, describe_series, Statistic, StatisticsOptions };
+    use crate::{
+        data::{ DataType, DataTypeValue, DataCategory },
+        temporal::Date,
+        ordinal::OrdinalScale,
+        polars::DataVec
+    };
+
+    #[test]
+    fn numerical_column() {
+        let series = Series::new("age", &[Some(30i64), None, Some(20), Some(40), Some(30)]);
+        let options = StatisticsOptions { quantiles: vec![0.0, 0.5, 1.0], top_k: 2 };
+        let statistics = describe_series(&series, &options).unwrap();
+        assert_eq!(statistics.data_type, DataType::I64);
+        assert_eq!((statistics.count, statistics.null_count, statistics.distinct_count), (4, 1, 3));
+        assert_eq!(statistics.min, Some(DataTypeValue::I64(20)));
+        assert_eq!(statistics.max, Some(DataTypeValue::I64(40)));
+        assert_eq!(statistics.mean, Some(DataTypeValue::F64(30.0)));
+        assert!((statistics.std.unwrap() - 8.164_965_809).abs() < 1e-9);
+        assert_eq!(statistics.quantiles, vec![
+            (0.0, DataTypeValue::I64(20)), (0.5, DataTypeValue::I64(30)),
+            (1.0, DataTypeValue::I64(40))
+        ]);
+        assert_eq!(statistics.mode, Some(DataTypeValue::I64(30)));
+        assert_eq!(statistics.top_k, vec![
+            (DataTypeValue::I64(30), 2), (DataTypeValue::I64(20), 1)
+        ]);
+        assert!(statistics.meaningful().contains(&Statistic::Std));
+
+        let dates = describe(&DataVec::from(vec![Date(0), Date(3)]));
+        assert_eq!(dates.mean, Some(DataTypeValue::Date(Date(2))));
+        assert_eq!(dates.std, Some(4.5f64.sqrt()));
+    }
+
+    #[test]
+    fn categorical_and_ordinal_columns() {
+        let colors = DataVec::from(vec![
+            Some("red".to_string()), Some("blue".to_string()), None, Some("red".to_string())
+        ]);
+        let statistics = describe(&colors);
+        assert_eq!(statistics.category, DataCategory::Categorical);
+        assert_eq!((statistics.count, statistics.distinct_count), (3, 2));
+        assert_eq!(statistics.mode, Some(DataTypeValue::String("red".to_string())));
+        assert_eq!((statistics.min, statistics.mean, statistics.std), (None, None, None));
+        assert!(statistics.quantiles.is_empty());
+        assert!(!Statistic::Min.is_meaningful_for(DataCategory::Categorical));
+
+        let scale = OrdinalScale::new(&["low", "mid", "high"]).unwrap();
+        let level = |x: &str| DataTypeValue::Ordinal(scale.value(x).unwrap());
+        let values = ["high", "low", "mid", "mid"].iter().map(|x| Some(level(x)));
+        let options = StatisticsOptions { quantiles: vec![0.5], top_k: 1 };
+        let statistics = describe_values(values, DataType::Ordinal, &options);
+        assert_eq!(statistics.category, DataCategory::Ordinal);
+        assert_eq!((statistics.min, statistics.max), (Some(level("low")), Some(level("high"))));
+        assert_eq!(statistics.quantiles, vec![(0.5, level("mid"))]);
+        assert_eq!(statistics.top_k, vec![(level("mid"), 2)]);
+        assert_eq!(statistics.mean, None);
+
+        let empty = describe(&DataVec::from(Vec::<Option<f64>>::from([None, None])));
+        assert_eq!((empty.count, empty.null_count, empty.mean), (0, 2, None));
+    }
+}
@@ -0,0 +1,245 @@
+use std::{
+    rc::Rc,
+    cmp::Ordering,
+    collections::{ HashMap, HashSet },
+    fmt::{ Display, Formatter, Result as FmtResult }
+};
+
+use crate::distances::Distance;
+
+// forest of named nodes, the roots hang under an implicit common root of depth 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Taxonomy {
+    names: Vec<Rc<str>>,
+    parents: Vec<Option<usize>>,
+    depths: Vec<usize>,
+    index: HashMap<Rc<str>, usize>
+}
+
+impl Taxonomy {
+    // (node, parent) pairs, parents missing from the list become roots
+    pub fn from_edges(edges: &[(&str, Option<&str>)]) -> Result<Rc<Taxonomy>, String> {
+        let mut taxonomy = Taxonomy {
+            names: Vec::new(), parents: Vec::new(), depths: Vec::new(), index: HashMap::new()
+        };
+        for (node, _) in edges {
+            if taxonomy.index.contains_key(*node) {
+                return Err(format!("duplicated taxonomy node {}", node))
+            }
+            taxonomy.push(node, None);
+        }
+        for (node, parent) in edges {
+            if let Some(parent) = parent {
+                let parent = match taxonomy.index.get(*parent) {
+                    Some(parent) => *parent,
+                    None => taxonomy.push(parent, None)
+                };
+                let node = taxonomy.index[*node];
+                taxonomy.parents[node] = Some(parent);
+            }
+        }
+        for node in 0..taxonomy.names.len() {
+            taxonomy.depths[node] = taxonomy.depth_of(node)
+                .ok_or_else(|| format!("taxonomy cycle through {}", taxonomy.names[node]))?;
+        }
+        Ok(Rc::new(taxonomy))
+    }
+
+    // every prefix of "Electronics/Phones" becomes a node named by its full path
+    pub fn from_paths(paths: &[&str], separator: &str) -> Result<Rc<Taxonomy>, String> {
+        let mut edges: Vec<(String, Option<String>)> = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        for path in paths {
+            let mut parent: Option<String> = None;
+            let mut prefix = String::new();
+            for part in path.split(separator) {
+                if part.is_empty() {
+                    return Err(format!("empty segment in taxonomy path {}", path))
+                }
+                if !prefix.is_empty() { prefix.push_str(separator) }
+                prefix.push_str(part);
+                if seen.insert(prefix.clone()) {
+                    edges.push((prefix.clone(), parent.clone()));
+                }
+                parent = Some(prefix.clone());
+            }
+        }
+        let edges: Vec<(&str, Option<&str>)> = edges.iter()
+            .map(|(node, parent)| (node.as_str(), parent.as_deref()))
+            .collect();
+        Taxonomy::from_edges(&edges)
+    }
+
+    fn push(&mut self, name: &str, parent: Option<usize>) -> usize {
+        let name: Rc<str> = Rc::from(name);
+        self.names.push(name.clone());
+        self.parents.push(parent);
+        self.depths.push(0);
+        self.index.insert(name, self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    // None for cycles
+    fn depth_of(&self, node: usize) -> Option<usize> {
+        let mut depth = 1;
+        let mut current = node;
+        while let Some(parent) = self.parents[current] {
+            depth += 1;
+            if depth > self.names.len() { return None }
+            current = parent;
+        }
+        Some(depth)
+    }
+
+    pub fn len(&self) -> usize { self.names.len() }
+
+    pub fn is_empty(&self) -> bool { self.names.is_empty() }
+
+    pub fn contains(&self, name: &str) -> bool { self.index.contains_key(name) }
+
+    pub fn depth(&self, name: &str) -> Option<usize> { Some(self.depths[*self.index.get(name)?]) }
+
+    pub fn parent(&self, name: &str) -> Option<&str> {
+        Some(&self.names[self.parents[*self.index.get(name)?]?])
+    }
+
+    pub fn value(self: &Rc<Self>, name: &str) -> Option<TaxonomyNode> {
+        TaxonomyNode::new(self, name)
+    }
+
+    // None when the nodes only share the implicit root
+    fn lowest_common_ancestor(&self, mut lhs: usize, mut rhs: usize) -> Option<usize> {
+        while self.depths[lhs] > self.depths[rhs] { lhs = self.parents[lhs]? }
+        while self.depths[rhs] > self.depths[lhs] { rhs = self.parents[rhs]? }
+        while lhs != rhs {
+            lhs = self.parents[lhs]?;
+            rhs = self.parents[rhs]?;
+        }
+        Some(lhs)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TaxonomyNode {
+    taxonomy: Rc<Taxonomy>,
+    node: usize
+}
+
+impl TaxonomyNode {
+    pub fn new(taxonomy: &Rc<Taxonomy>, name: &str) -> Option<TaxonomyNode> {
+        Some(TaxonomyNode { taxonomy: taxonomy.clone(), node: *taxonomy.index.get(name)? })
+    }
+
+    pub fn taxonomy(&self) -> &Rc<Taxonomy> { &self.taxonomy }
+
+    pub fn name(&self) -> &str { &self.taxonomy.names[self.node] }
+
+    pub fn depth(&self) -> usize { self.taxonomy.depths[self.node] }
+
+    pub fn parent(&self) -> Option<TaxonomyNode> {
+        let node = self.taxonomy.parents[self.node]?;
+        Some(TaxonomyNode { taxonomy: self.taxonomy.clone(), node })
+    }
+
+    pub fn same_taxonomy(&self, other: &TaxonomyNode) -> bool {
+        Rc::ptr_eq(&self.taxonomy, &other.taxonomy) || self.taxonomy == other.taxonomy
+    }
+
+    pub fn lowest_common_ancestor(&self, other: &TaxonomyNode) -> Option<TaxonomyNode> {
+        if !self.same_taxonomy(other) { return None }
+        let node = self.taxonomy.lowest_common_ancestor(self.node, other.node)?;
+        Some(TaxonomyNode { taxonomy: self.taxonomy.clone(), node })
+    }
+
+    // number of edges between the nodes through their lowest common ancestor
+    pub fn path_length(&self, other: &TaxonomyNode) -> Option<usize> {
+        if !self.same_taxonomy(other) { return None }
+        let lca_depth = self.lowest_common_ancestor(other).map(|x| x.depth()).unwrap_or(0);
+        Some(self.depth() + other.depth() - 2 * lca_depth)
+    }
+
+    pub fn is_ancestor_of(&self, other: &TaxonomyNode) -> bool {
+        self.lowest_common_ancestor(other).map(|x| x.node == self.node).unwrap_or(false)
+    }
+}
+
+impl PartialEq for TaxonomyNode {
+    fn eq(&self, other: &TaxonomyNode) -> bool {
+        self.node == other.node && self.same_taxonomy(other)
+    }
+}
+
+// by name, None across taxonomies
+impl PartialOrd for TaxonomyNode {
+    fn partial_cmp(&self, other: &TaxonomyNode) -> Option<Ordering> {
+        if self.same_taxonomy(other) { self.name().partial_cmp(other.name()) } else { None }
+    }
+}
+
+impl Display for TaxonomyNode {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.name())
+    }
+}
+
+// path length normalized by the summed depths, 0 for the same node and 1 for unrelated roots,
+// NaN across taxonomies
+impl Distance for TaxonomyNode {
+    fn distance(&self, v: &TaxonomyNode) -> f64 {
+        match self.path_length(v) {
+            Some(length) => length as f64 / (self.depth() + v.depth()) as f64,
+            None => f64::NAN
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::Taxonomy;
+    use crate::{
+        sensor::SensorData,
+        data::{ DataCategory, DataDeductor }
+    };
+
+    #[test]
+    fn lowest_common_ancestor_distance() {
+        let taxonomy = Taxonomy::from_paths(&[
+            "Electronics/Phones/Smartphones", "Electronics/Laptops", "Food/Fruit"
+        ], "/").unwrap();
+        assert_eq!(taxonomy.len(), 6);
+        assert_eq!(taxonomy.parent("Electronics/Laptops"), Some("Electronics"));
+
+        let phones = taxonomy.value("Electronics/Phones").unwrap();
+        let smartphones = taxonomy.value("Electronics/Phones/Smartphones").unwrap();
+        let laptops = taxonomy.value("Electronics/Laptops").unwrap();
+        let fruit = taxonomy.value("Food/Fruit").unwrap();
+
+        assert_eq!(phones.lowest_common_ancestor(&laptops).unwrap().name(), "Electronics");
+        assert_eq!(SensorData::distance(&phones, &laptops), 0.5);
+        assert_eq!(SensorData::distance(&phones, &fruit), 1.0);
+        assert_eq!(SensorData::distance(&phones, &phones.clone()), 0.0);
+        assert_eq!(SensorData::distance(&phones, &smartphones), 0.2);
+        assert!(phones.is_ancestor_of(&smartphones) && !smartphones.is_ancestor_of(&phones));
+        assert_eq!(phones.data_category(), DataCategory::Categorical);
+
+        let other = Taxonomy::from_paths(&["Food/Fruit", "Food/Vegetables"], "/").unwrap();
+        assert!(SensorData::distance(&fruit, &other.value("Food/Vegetables").unwrap()).is_nan());
+    }
+
+    #[test]
+    fn user_supplied_tree() {
+        let icd = Taxonomy::from_edges(&[
+            ("J00-J99", None), ("J09-J18", Some("J00-J99")), ("J10", Some("J09-J18")),
+            ("J11", Some("J09-J18")), ("J40-J47", Some("J00-J99")), ("J45", Some("J40-J47"))
+        ]).unwrap();
+        let j10 = icd.value("J10").unwrap();
+        assert_eq!(j10.path_length(&icd.value("J11").unwrap()), Some(2));
+        assert_eq!(j10.path_length(&icd.value("J45").unwrap()), Some(4));
+
+        assert!(Taxonomy::from_edges(&[("a", Some("b")), ("b", Some("a"))]).is_err());
+        assert!(Taxonomy::from_edges(&[("a", None), ("a", None)]).is_err());
+        assert!(Taxonomy::from_paths(&["a//b"], "/").is_err());
+    }
+}
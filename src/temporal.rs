@@ -0,0 +1,298 @@
+use std::{
+    rc::Rc,
+    cmp::Ordering,
+    hash::{ Hash, Hasher },
+    fmt::{ Display, Formatter, Result as FmtResult }
+};
+
+use chrono::{ DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime };
+
+use crate::distances::Distance;
+
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
+const NANOSECONDS_IN_SECOND: f64 = 1_000_000_000.0;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimeUnit {
+    Nanoseconds,
+    Microseconds,
+    #[default]
+    Milliseconds
+}
+
+impl TimeUnit {
+    pub fn nanoseconds(&self) -> i64 {
+        match self {
+            TimeUnit::Nanoseconds => 1,
+            TimeUnit::Microseconds => 1_000,
+            TimeUnit::Milliseconds => 1_000_000
+        }
+    }
+
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            TimeUnit::Nanoseconds => "ns",
+            TimeUnit::Microseconds => "us",
+            TimeUnit::Milliseconds => "ms"
+        }
+    }
+}
+
+impl Display for TimeUnit {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl From<polars::prelude::TimeUnit> for TimeUnit {
+    fn from(unit: polars::prelude::TimeUnit) -> TimeUnit {
+        match unit {
+            polars::prelude::TimeUnit::Nanoseconds => TimeUnit::Nanoseconds,
+            polars::prelude::TimeUnit::Microseconds => TimeUnit::Microseconds,
+            polars::prelude::TimeUnit::Milliseconds => TimeUnit::Milliseconds
+        }
+    }
+}
+
+impl From<TimeUnit> for polars::prelude::TimeUnit {
+    fn from(unit: TimeUnit) -> polars::prelude::TimeUnit {
+        match unit {
+            TimeUnit::Nanoseconds => polars::prelude::TimeUnit::Nanoseconds,
+            TimeUnit::Microseconds => polars::prelude::TimeUnit::Microseconds,
+            TimeUnit::Milliseconds => polars::prelude::TimeUnit::Milliseconds
+        }
+    }
+}
+
+// days elapsed since 1970-01-01, the same physical representation polars uses
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Date(pub i32);
+
+impl Date {
+    pub fn from_naive_date(date: NaiveDate) -> Date {
+        Date(date.num_days_from_ce() - UNIX_EPOCH_DAYS_FROM_CE)
+    }
+
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Date> {
+        Some(Date::from_naive_date(NaiveDate::from_ymd_opt(year, month, day)?))
+    }
+
+    pub fn to_naive_date(&self) -> Option<NaiveDate> {
+        NaiveDate::from_num_days_from_ce_opt(self.0.checked_add(UNIX_EPOCH_DAYS_FROM_CE)?)
+    }
+
+    pub fn parse(s: &str) -> Option<Date> {
+        Some(Date::from_naive_date(NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok()?))
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self.to_naive_date() {
+            Some(date) => write!(f, "{}", date),
+            None => write!(f, "{}d", self.0)
+        }
+    }
+}
+
+// distance in days
+impl Distance for Date {
+    fn distance(&self, v: &Date) -> f64 {
+        (self.0 as f64 - v.0 as f64).abs()
+    }
+}
+
+// instant elapsed since 1970-01-01 00:00:00 UTC in the given unit,
+// the timezone is only carried along for presentation
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Datetime {
+    pub timestamp: i64,
+    pub unit: TimeUnit,
+    pub timezone: Option<Rc<str>>
+}
+
+impl Datetime {
+    pub fn new(timestamp: i64, unit: TimeUnit, timezone: Option<Rc<str>>) -> Datetime {
+        Datetime { timestamp, unit, timezone }
+    }
+
+    pub fn from_naive_datetime(datetime: NaiveDateTime, unit: TimeUnit) -> Option<Datetime> {
+        let datetime = datetime.and_utc();
+        let nanoseconds = datetime.timestamp() as i128 * 1_000_000_000
+            + datetime.timestamp_subsec_nanos() as i128;
+        let timestamp = nanoseconds.div_euclid(unit.nanoseconds() as i128);
+        Some(Datetime::new(i64::try_from(timestamp).ok()?, unit, None))
+    }
+
+    pub fn to_naive_datetime(&self) -> Option<NaiveDateTime> {
+        let nanoseconds = self.nanoseconds();
+        let seconds = i64::try_from(nanoseconds.div_euclid(1_000_000_000)).ok()?;
+        let subsec_nanoseconds = nanoseconds.rem_euclid(1_000_000_000) as u32;
+        Some(DateTime::from_timestamp(seconds, subsec_nanoseconds)?.naive_utc())
+    }
+
+    pub fn parse(s: &str, unit: TimeUnit) -> Option<Datetime> {
+        let s = s.trim();
+        // the second form is the Display output
+        let offset = DateTime::parse_from_rfc3339(s)
+            .or_else(|_| DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f %:z"));
+        if let Ok(datetime) = offset {
+            let mut result = Datetime::from_naive_datetime(datetime.naive_utc(), unit)?;
+            result.timezone = Some(datetime.offset().to_string().into());
+            return Some(result)
+        }
+        let datetime = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%d %H:%M"].iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+            .or_else(|| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0))?;
+        Datetime::from_naive_datetime(datetime, unit)
+    }
+
+    pub fn nanoseconds(&self) -> i128 {
+        self.timestamp as i128 * self.unit.nanoseconds() as i128
+    }
+
+    pub fn distance_in(&self, v: &Datetime, unit: TimeUnit) -> f64 {
+        (self.nanoseconds() - v.nanoseconds()).abs() as f64 / unit.nanoseconds() as f64
+    }
+}
+
+impl PartialEq for Datetime {
+    fn eq(&self, other: &Datetime) -> bool { self.nanoseconds() == other.nanoseconds() }
+}
+
+impl Eq for Datetime {}
+
+impl PartialOrd for Datetime {
+    fn partial_cmp(&self, other: &Datetime) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Datetime {
+    fn cmp(&self, other: &Datetime) -> Ordering { self.nanoseconds().cmp(&other.nanoseconds()) }
+}
+
+impl Hash for Datetime {
+    fn hash<H: Hasher>(&self, state: &mut H) { self.nanoseconds().hash(state) }
+}
+
+// wall time in the timezone if it is a fixed offset, otherwise UTC labelled as such
+impl Display for Datetime {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let datetime = match self.to_naive_datetime() {
+            Some(datetime) => datetime,
+            None => return write!(f, "{}{}", self.timestamp, self.unit.suffix())
+        };
+        match &self.timezone {
+            Some(timezone) => match timezone.parse::<FixedOffset>() {
+                Ok(offset) => write!(f, "{}", datetime.and_utc().with_timezone(&offset)),
+                Err(_) => write!(f, "{} UTC", datetime)
+            },
+            None => write!(f, "{}", datetime)
+        }
+    }
+}
+
+// distance in seconds
+impl Distance for Datetime {
+    fn distance(&self, v: &Datetime) -> f64 {
+        (self.nanoseconds() - v.nanoseconds()).abs() as f64 / NANOSECONDS_IN_SECOND
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Duration {
+    pub value: i64,
+    pub unit: TimeUnit
+}
+
+impl Duration {
+    pub fn new(value: i64, unit: TimeUnit) -> Duration { Duration { value, unit } }
+
+    pub fn parse(s: &str, unit: TimeUnit) -> Option<Duration> {
+        let s = s.trim();
+        let s = s.strip_suffix(unit.suffix()).unwrap_or(s);
+        Some(Duration::new(s.parse().ok()?, unit))
+    }
+
+    pub fn nanoseconds(&self) -> i128 {
+        self.value as i128 * self.unit.nanoseconds() as i128
+    }
+
+    pub fn distance_in(&self, v: &Duration, unit: TimeUnit) -> f64 {
+        (self.nanoseconds() - v.nanoseconds()).abs() as f64 / unit.nanoseconds() as f64
+    }
+}
+
+impl PartialEq for Duration {
+    fn eq(&self, other: &Duration) -> bool { self.nanoseconds() == other.nanoseconds() }
+}
+
+impl Eq for Duration {}
+
+impl PartialOrd for Duration {
+    fn partial_cmp(&self, other: &Duration) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Duration {
+    fn cmp(&self, other: &Duration) -> Ordering { self.nanoseconds().cmp(&other.nanoseconds()) }
+}
+
+impl Hash for Duration {
+    fn hash<H: Hasher>(&self, state: &mut H) { self.nanoseconds().hash(state) }
+}
+
+impl Display for Duration {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}{}", self.value, self.unit.suffix())
+    }
+}
+
+// distance in seconds
+impl Distance for Duration {
+    fn distance(&self, v: &Duration) -> f64 {
+        (self.nanoseconds() - v.nanoseconds()).abs() as f64 / NANOSECONDS_IN_SECOND
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{ Date, Datetime, Duration, TimeUnit };
+    use crate::distances::Distance;
+
+    #[test]
+    fn date_roundtrip() {
+        let date = Date::parse("2022-03-01").unwrap();
+        assert_eq!(date, Date::from_ymd(2022, 3, 1).unwrap());
+        assert_eq!(Date::parse("1970-01-01").unwrap(), Date(0));
+        assert_eq!(date.to_string(), "2022-03-01");
+        assert_eq!(date.distance(&Date::parse("2022-02-27").unwrap()), 2.0);
+    }
+
+    #[test]
+    fn datetime_units() {
+        let ms = Datetime::parse("2022-03-01 10:00:00", TimeUnit::Milliseconds).unwrap();
+        let ns = Datetime::parse("2022-03-01T10:00:01.5", TimeUnit::Nanoseconds).unwrap();
+        assert!(ms < ns);
+        assert_eq!(ms.distance(&ns), 1.5);
+        assert_eq!(ms.distance_in(&ns, TimeUnit::Milliseconds), 1500.0);
+
+        let offset = Datetime::parse("2022-03-01T12:00:00+02:00", TimeUnit::Microseconds).unwrap();
+        assert_eq!(offset, ms);
+        assert_eq!(offset.to_string(), "2022-03-01 12:00:00 +02:00");
+        assert_eq!(Datetime::parse(&offset.to_string(), TimeUnit::Milliseconds), Some(offset.clone()));
+        let named = Datetime::new(ms.timestamp, ms.unit, Some("Europe/Warsaw".into()));
+        assert_eq!(named.to_string(), "2022-03-01 10:00:00 UTC");
+    }
+
+    #[test]
+    fn duration_units() {
+        let seconds = Duration::new(2_000, TimeUnit::Milliseconds);
+        assert_eq!(seconds, Duration::parse("2000000us", TimeUnit::Microseconds).unwrap());
+        assert_eq!(seconds.distance(&Duration::new(500_000_000, TimeUnit::Nanoseconds)), 1.5);
+    }
+}
@@ -0,0 +1,280 @@
+use std::{
+    rc::Rc,
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::{ Display, Formatter, Result as FmtResult }
+};
+
+use crate::{ distances::Distance, metric::Metric };
+
+// all metrics work on unicode scalar values and are normalized to [0, 1]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StringMetric {
+    // 0 for equal strings, 1 otherwise
+    Exact,
+    // edits divided by the length of the longer string
+    #[default]
+    Levenshtein,
+    // as Levenshtein, adjacent transpositions count as one edit
+    DamerauLevenshtein,
+    // 1 - Jaro-Winkler similarity
+    JaroWinkler,
+    // 1 - Dice coefficient of the character n-grams
+    NGram(usize)
+}
+
+impl StringMetric {
+    pub fn distance(&self, lhs: &str, rhs: &str) -> f64 {
+        if lhs == rhs { return 0.0 }
+        let (lhs, rhs): (Vec<char>, Vec<char>) = (lhs.chars().collect(), rhs.chars().collect());
+        let longest = lhs.len().max(rhs.len()) as f64;
+        match self {
+            StringMetric::Exact => 1.0,
+            StringMetric::Levenshtein => levenshtein_chars(&lhs, &rhs) as f64 / longest,
+            StringMetric::DamerauLevenshtein => {
+                damerau_levenshtein_chars(&lhs, &rhs) as f64 / longest
+            }
+            StringMetric::JaroWinkler => 1.0 - jaro_winkler_chars(&lhs, &rhs),
+            StringMetric::NGram(n) => 1.0 - ngram_similarity_chars(&lhs, &rhs, *n)
+        }
+    }
+}
+
+// attachable to sensors over the plain string types through Sensor::metric
+impl Metric<str> for StringMetric {
+    fn distance(&self, lhs: &str, rhs: &str) -> f64 { StringMetric::distance(self, lhs, rhs) }
+}
+
+impl Metric<String> for StringMetric {
+    fn distance(&self, lhs: &String, rhs: &String) -> f64 { StringMetric::distance(self, lhs, rhs) }
+}
+
+impl Metric<Rc<str>> for StringMetric {
+    fn distance(&self, lhs: &Rc<str>, rhs: &Rc<str>) -> f64 {
+        StringMetric::distance(self, lhs, rhs)
+    }
+}
+
+fn levenshtein_chars(lhs: &[char], rhs: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=rhs.len()).collect();
+    let mut current = vec![0; rhs.len() + 1];
+    for (i, a) in lhs.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b) in rhs.iter().enumerate() {
+            let substitution = previous[j] + (a != b) as usize;
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[rhs.len()]
+}
+
+// unrestricted variant, a substring may be edited again after a transposition
+fn damerau_levenshtein_chars(lhs: &[char], rhs: &[char]) -> usize {
+    let (n, m) = (lhs.len(), rhs.len());
+    let infinity = n + m;
+    let mut last_row: HashMap<char, usize> = HashMap::new();
+    let mut d = vec![vec![0; m + 2]; n + 2];
+    d[0][0] = infinity;
+    for i in 0..=n {
+        d[i + 1][0] = infinity;
+        d[i + 1][1] = i;
+    }
+    for j in 0..=m {
+        d[0][j + 1] = infinity;
+        d[1][j + 1] = j;
+    }
+    for i in 1..=n {
+        let mut last_column = 0;
+        for j in 1..=m {
+            let k = last_row.get(&rhs[j - 1]).copied().unwrap_or(0);
+            let l = last_column;
+            let cost = if lhs[i - 1] == rhs[j - 1] { last_column = j; 0 } else { 1 };
+            d[i + 1][j + 1] = (d[i][j] + cost)
+                .min(d[i + 1][j] + 1)
+                .min(d[i][j + 1] + 1)
+                .min(d[k][l] + (i - k - 1) + 1 + (j - l - 1));
+        }
+        last_row.insert(lhs[i - 1], i);
+    }
+    d[n + 1][m + 1]
+}
+
+fn jaro_chars(lhs: &[char], rhs: &[char]) -> f64 {
+    if lhs.is_empty() && rhs.is_empty() { return 1.0 }
+    if lhs.is_empty() || rhs.is_empty() { return 0.0 }
+    let window = (lhs.len().max(rhs.len()) / 2).saturating_sub(1);
+    let mut lhs_matched = vec![false; lhs.len()];
+    let mut rhs_matched = vec![false; rhs.len()];
+    let mut matches = 0;
+    for (i, a) in lhs.iter().enumerate() {
+        let (start, end) = (i.saturating_sub(window), (i + window + 1).min(rhs.len()));
+        for j in start..end {
+            if !rhs_matched[j] && rhs[j] == *a {
+                lhs_matched[i] = true;
+                rhs_matched[j] = true;
+                matches += 1;
+                break
+            }
+        }
+    }
+    if matches == 0 { return 0.0 }
+
+    let lhs_order = lhs.iter().zip(&lhs_matched).filter(|(_, m)| **m).map(|(c, _)| c);
+    let rhs_order = rhs.iter().zip(&rhs_matched).filter(|(_, m)| **m).map(|(c, _)| c);
+    let transpositions = lhs_order.zip(rhs_order).filter(|(a, b)| a != b).count() / 2;
+    let matches = matches as f64;
+    (matches / lhs.len() as f64 + matches / rhs.len() as f64
+        + (matches - transpositions as f64) / matches) / 3.0
+}
+
+// prefix scale 0.1 over at most 4 common leading characters
+fn jaro_winkler_chars(lhs: &[char], rhs: &[char]) -> f64 {
+    let jaro = jaro_chars(lhs, rhs);
+    let prefix = lhs.iter().zip(rhs).take(4).take_while(|(a, b)| a == b).count();
+    jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
+fn ngrams(chars: &[char], n: usize) -> HashMap<&[char], usize> {
+    let mut grams = HashMap::new();
+    if chars.is_empty() { return grams }
+    // strings shorter than n are a single gram
+    for gram in chars.windows(n.min(chars.len())) { *grams.entry(gram).or_insert(0) += 1 }
+    grams
+}
+
+fn ngram_similarity_chars(lhs: &[char], rhs: &[char], n: usize) -> f64 {
+    if lhs.is_empty() && rhs.is_empty() { return 1.0 }
+    let n = n.max(1);
+    let (lhs, rhs) = (ngrams(lhs, n), ngrams(rhs, n));
+    let common: usize = lhs.iter().map(|(gram, x)| *x.min(rhs.get(gram).unwrap_or(&0))).sum();
+    let total: usize = lhs.values().sum::<usize>() + rhs.values().sum::<usize>();
+    2.0 * common as f64 / total as f64
+}
+
+pub fn levenshtein(lhs: &str, rhs: &str) -> usize {
+    levenshtein_chars(&lhs.chars().collect::<Vec<_>>(), &rhs.chars().collect::<Vec<_>>())
+}
+
+pub fn damerau_levenshtein(lhs: &str, rhs: &str) -> usize {
+    damerau_levenshtein_chars(&lhs.chars().collect::<Vec<_>>(), &rhs.chars().collect::<Vec<_>>())
+}
+
+pub fn jaro_winkler(lhs: &str, rhs: &str) -> f64 {
+    jaro_winkler_chars(&lhs.chars().collect::<Vec<_>>(), &rhs.chars().collect::<Vec<_>>())
+}
+
+pub fn ngram_similarity(lhs: &str, rhs: &str, n: usize) -> f64 {
+    ngram_similarity_chars(&lhs.chars().collect::<Vec<_>>(), &rhs.chars().collect::<Vec<_>>(), n)
+}
+
+// string shared between clones, compared in the metric both sides share
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Text {
+    text: Rc<str>,
+    metric: StringMetric
+}
+
+impl Text {
+    pub fn new(text: &str, metric: StringMetric) -> Text {
+        Text { text: Rc::from(text), metric }
+    }
+
+    pub fn from_rc(text: Rc<str>, metric: StringMetric) -> Text { Text { text, metric } }
+
+    pub fn as_str(&self) -> &str { &self.text }
+
+    pub fn metric(&self) -> StringMetric { self.metric }
+
+    pub fn with_metric(&self, metric: StringMetric) -> Text {
+        Text { text: self.text.clone(), metric }
+    }
+
+    pub fn distance_with(&self, other: &Text, metric: StringMetric) -> f64 {
+        metric.distance(&self.text, &other.text)
+    }
+}
+
+impl PartialEq for Text {
+    fn eq(&self, other: &Text) -> bool { self.text == other.text }
+}
+
+impl PartialOrd for Text {
+    fn partial_cmp(&self, other: &Text) -> Option<Ordering> { self.text.partial_cmp(&other.text) }
+}
+
+impl Display for Text {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.text)
+    }
+}
+
+// NaN for texts with different metrics, so that the distance is symmetric
+impl Distance for Text {
+    fn distance(&self, v: &Text) -> f64 {
+        if self.metric != v.metric { return f64::NAN }
+        self.distance_with(v, self.metric)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use std::rc::Rc;
+
+    use super::{ Text, StringMetric, levenshtein, damerau_levenshtein, jaro_winkler };
+    use crate::{ sensor::SensorData, metric::Metric };
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("żółw", "zołw"), 2);
+        assert_eq!(levenshtein("ca", "abc"), 3);
+        assert_eq!(damerau_levenshtein("ca", "abc"), 2);
+        assert_eq!(damerau_levenshtein("abcdef", "abdcef"), 1);
+        assert!((jaro_winkler("MARTHA", "MARHTA") - 0.961_111).abs() < 1e-6);
+        assert!((jaro_winkler("DIXON", "DICKSONX") - 0.813_333).abs() < 1e-6);
+        assert_eq!(jaro_winkler("", ""), 1.0);
+        assert_eq!(jaro_winkler("abc", ""), 0.0);
+
+        let metric = StringMetric::NGram(2);
+        assert_eq!(metric.distance("night", "nacht"), 0.75);
+        assert_eq!(metric.distance("a", "a"), 0.0);
+        assert_eq!(metric.distance("a", "b"), 1.0);
+        assert_eq!(StringMetric::Exact.distance("a", "b"), 1.0);
+    }
+
+    #[test]
+    fn selectable_metrics() {
+        let query = Text::new("John Smith", StringMetric::Levenshtein);
+        let stored = Text::new("Jon Smith", StringMetric::Levenshtein);
+        assert_eq!(SensorData::distance(&query, &stored), 0.1);
+        assert_eq!(SensorData::distance(&stored, &query), 0.1);
+        let stored = stored.with_metric(StringMetric::JaroWinkler);
+        let fuzzy = query.with_metric(StringMetric::JaroWinkler);
+        assert!(SensorData::distance(&fuzzy, &stored) < 0.05);
+        assert!(SensorData::distance(&query, &stored).is_nan());
+        assert!(SensorData::distance(&stored, &query).is_nan());
+        assert_eq!(query.distance_with(&stored, StringMetric::Exact), 1.0);
+
+        for metric in [
+            StringMetric::Levenshtein, StringMetric::DamerauLevenshtein,
+            StringMetric::JaroWinkler, StringMetric::NGram(3)
+        ] {
+            let distance = metric.distance("Zażółć", "gęślą jaźń");
+            assert!((0.0..=1.0).contains(&distance), "{:?} {}", metric, distance);
+            assert_eq!(metric.distance("jaźń", "jaźń"), 0.0);
+        }
+        assert!(query.equals(&query.with_metric(StringMetric::Exact)));
+
+        let names: &dyn Metric<String> = &StringMetric::Levenshtein;
+        assert_eq!(names.distance(&"John Smith".to_string(), &"Jon Smith".to_string()), 0.1);
+        let codes: &dyn Metric<Rc<str>> = &StringMetric::DamerauLevenshtein;
+        assert_eq!(codes.distance(&Rc::from("ab12"), &Rc::from("ba12")), 0.25);
+        assert_eq!(Metric::<str>::distance(&StringMetric::Exact, "a", "a"), 0.0);
+    }
+}